@@ -0,0 +1,60 @@
+use advent_of_code_2021::days::d04::{parse_game, Bingo};
+use advent_of_code_2021::utils::cli::Args;
+use advent_of_code_2021::utils::read_file_lines;
+
+const USAGE: &str = "Usage: bingo_odds [FILE] [--trials N] [--seed S]";
+
+// Probabilities of winning first and last over random draw orders, next to the
+// boards `part1` and `part2` pick with the puzzle's draw order.
+fn main() {
+    let mut filename = "input/04.txt".to_string();
+    let mut trials = 10_000;
+    let mut seed = 2021;
+
+    let mut args = Args::new(USAGE);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--trials" => trials = args.value(&arg),
+            "--seed" => seed = args.value(&arg),
+            _ => filename = args.file(arg),
+        }
+    }
+
+    let game = parse_game(&read_file_lines(&filename));
+    let bingo = Bingo::puzzle();
+    let timeline = bingo.play(&game);
+    let odds = bingo.monte_carlo(&game, trials, seed);
+
+    println!("{} random draw orders (seed {})", odds.trials, odds.seed);
+    match odds.draws_to_first_win {
+        Some(draws) => println!(
+            "Draws until the first win: {:.2} (95% CI {:.2}..{:.2}), no winner in {} trials\n",
+            draws.value, draws.low, draws.high, odds.no_win
+        ),
+        None => println!(
+            "Draws until the first win: too few winners, no winner in {} trials\n",
+            odds.no_win
+        ),
+    }
+    println!(
+        "{:>5}  {:>8}  {:<15}  {:>8}  95% CI",
+        "Board", "P(first)", "95% CI", "P(last)"
+    );
+    for board in &odds.boards {
+        let note = match (timeline.first(), timeline.last()) {
+            (Some(first), _) if first.board == board.board => "  <- part 1",
+            (_, Some(last)) if last.board == board.board => "  <- part 2",
+            _ => "",
+        };
+        let row = format!(
+            "{:>5}  {:>8.4}  {:<15}  {:>8.4}  {:<15}{}",
+            board.board,
+            board.first.value,
+            format!("{:.4}..{:.4}", board.first.low, board.first.high),
+            board.last.value,
+            format!("{:.4}..{:.4}", board.last.low, board.last.high),
+            note
+        );
+        println!("{}", row.trim_end());
+    }
+}
@@ -0,0 +1,40 @@
+use advent_of_code_2021::days::d02::script::run_script;
+use advent_of_code_2021::days::d02::{depth_plot_svg, trajectory_csv, Aimed, Plain};
+use advent_of_code_2021::utils::cli::{exit_with, write, Args};
+use std::fs;
+
+const USAGE: &str = "Usage: dive_plot [FILE] [--csv OUT] [--svg OUT]";
+
+// FILE is a day 2 command list or a script (see `d02::script`). Without --csv or
+// --svg the trajectories are printed as CSV.
+fn main() {
+    let mut filename = "input/02.txt".to_string();
+    let mut csv = None;
+    let mut svg = None;
+
+    let mut args = Args::new(USAGE);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--csv" => csv = Some(args.value::<String>(&arg)),
+            "--svg" => svg = Some(args.value::<String>(&arg)),
+            _ => filename = args.file(arg),
+        }
+    }
+
+    let source = fs::read_to_string(&filename)
+        .unwrap_or_else(|error| exit_with(format!("{}: {}", filename, error)));
+    let (plain, aimed) = match (run_script(&source, Plain), run_script(&source, Aimed)) {
+        (Ok((_, plain)), Ok((_, aimed))) => (plain, aimed),
+        (Err(error), _) | (_, Err(error)) => exit_with(format!("{}: {}", filename, error)),
+    };
+    let trajectories = [("part 1", &plain[..]), ("part 2", &aimed[..])];
+
+    match &csv {
+        Some(out) => write(out, trajectory_csv(&trajectories)),
+        None if svg.is_none() => print!("{}", trajectory_csv(&trajectories)),
+        None => {}
+    }
+    if let Some(out) = &svg {
+        write(out, depth_plot_svg(&trajectories));
+    }
+}
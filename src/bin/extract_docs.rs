@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use std::fs;
+
+const DAYS_DIR: &str = "src/days";
+const DOCS_DIR: &str = "docs";
+const REPORT_FILE: &str = "target/report.tsv";
+
+enum Block {
+    Text(String),
+    List(Vec<String>),
+    Code(Vec<String>),
+}
+
+struct Day {
+    day: u8,
+    title: String,
+    parts: Vec<String>,
+    solvers: Vec<(String, usize)>,
+}
+
+fn escape(text: &str) -> String {
+    let mut result = String::new();
+    for c in text.chars() {
+        if "\\`*_<>[]".contains(c) {
+            result.push('\\');
+        }
+        result.push(c);
+    }
+    result
+}
+
+fn is_prose(line: &str) -> bool {
+    let words = line
+        .split_whitespace()
+        .filter(|word| word.chars().any(|c| c.is_alphabetic()))
+        .filter(|word| word.chars().all(|c| c.is_alphabetic() || ",.:;?!'\"()-".contains(c)))
+        .count();
+    words >= 5 && line.trim_end().ends_with(|c| ".?!:)\"".contains(c))
+}
+
+fn classify(paragraph: &[&str]) -> Block {
+    if paragraph.iter().all(|line| line.starts_with("    ")) {
+        Block::List(
+            paragraph
+                .iter()
+                .map(|line| {
+                    let indent = line.len() - line.trim_start().len();
+                    format!("{}- {}", " ".repeat(indent / 4 * 2 - 2), escape(line.trim()))
+                })
+                .collect(),
+        )
+    } else if paragraph.iter().any(|line| is_prose(line)) {
+        Block::Text(escape(&paragraph.join(" ")))
+    } else {
+        Block::Code(paragraph.iter().map(|line| line.to_string()).collect())
+    }
+}
+
+fn render(statement: &str) -> String {
+    let mut blocks = Vec::<Block>::new();
+    let mut paragraph = Vec::<&str>::new();
+    for line in statement.lines().chain(std::iter::once("")) {
+        if line.trim().is_empty() {
+            if !paragraph.is_empty() {
+                blocks.push(classify(&paragraph));
+                paragraph.clear();
+            }
+        } else {
+            paragraph.push(line);
+        }
+    }
+
+    blocks
+        .iter()
+        .map(|block| match block {
+            Block::Text(text) => text.clone(),
+            Block::List(items) => items.join("\n"),
+            Block::Code(lines) => format!("```\n{}\n```", lines.join("\n")),
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
+
+fn parse_day(day: u8, source: &str) -> Day {
+    let mut parts = Vec::<String>::new();
+    let mut solvers = Vec::<(String, usize)>::new();
+    let mut statement: Option<Vec<&str>> = None;
+
+    for (i, line) in source.lines().enumerate() {
+        match statement.as_mut() {
+            Some(lines) if line.starts_with("*/") => {
+                parts.push(lines.join("\n"));
+                statement = None;
+            }
+            Some(lines) => lines.push(line),
+            None if line.starts_with("/*") => statement = Some(Vec::new()),
+            None => {
+                if let Some(name) = line
+                    .strip_prefix("pub fn ")
+                    .and_then(|rest| rest.split('(').next())
+                    .filter(|name| name.starts_with("part"))
+                {
+                    solvers.push((name.to_string(), i + 1));
+                }
+            }
+        }
+    }
+
+    // The first sentence of the statement serves as a subtitle in the index.
+    let title = parts
+        .first()
+        .and_then(|part| part.lines().find(|line| !line.trim().is_empty()))
+        .filter(|line| !line.is_empty())
+        .map(|line| match line.find(". ") {
+            Some(end) => line[..=end].to_string(),
+            None => line.to_string(),
+        })
+        .unwrap_or_else(|| format!("Day {:02}", day));
+
+    Day {
+        day,
+        title,
+        parts,
+        solvers,
+    }
+}
+
+fn read_answers() -> HashMap<(u8, u8), String> {
+    let mut answers = HashMap::new();
+    if let Ok(report) = fs::read_to_string(REPORT_FILE) {
+        for line in report.lines() {
+            let fields = line.split('\t').collect::<Vec<&str>>();
+            if let [day, part, _input, _fingerprint, value] = fields[..] {
+                if let (Ok(day), Ok(part)) = (day.parse(), part.parse()) {
+                    answers.insert((day, part), value.to_string());
+                }
+            }
+        }
+    }
+    answers
+}
+
+fn write_day(day: &Day) {
+    let headings = ["Part One", "Part Two"];
+    let mut doc = format!("# Day {:02}\n", day.day);
+    for (i, part) in day.parts.iter().enumerate() {
+        let statement = render(part);
+        doc.push_str(&format!(
+            "\n## {}\n\n{}\n",
+            headings.get(i).unwrap_or(&"Notes"),
+            if statement.is_empty() {
+                "*The statement for this part was not recorded in the source.*"
+            } else {
+                &statement
+            }
+        ));
+    }
+    fs::write(format!("{}/day{:02}.md", DOCS_DIR, day.day), doc)
+        .expect("Something went wrong writing the docs");
+}
+
+fn write_index(days: &[Day]) {
+    let answers = read_answers();
+    let mut index = String::from("# Advent of Code 2021\n\n");
+    index.push_str("| Day | Statement | Solvers | Part 1 | Part 2 |\n");
+    index.push_str("| --- | --------- | ------- | ------ | ------ |\n");
+    for day in days {
+        let solvers = day
+            .solvers
+            .iter()
+            .map(|(name, line)| {
+                format!(
+                    "[`d{:02}::{}`](../{}/d{:02}.rs#L{})",
+                    day.day, name, DAYS_DIR, day.day, line
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+        let answer = |part: u8| {
+            answers
+                .get(&(day.day, part))
+                .map(|value| {
+                    // Multi-line answers (e.g. day 13) don't fit in a table cell.
+                    if value.contains("\\n") {
+                        "see output".to_string()
+                    } else {
+                        format!("`{}`", value)
+                    }
+                })
+                .unwrap_or_else(|| "-".to_string())
+        };
+        index.push_str(&format!(
+            "| {:02} | [{}](day{:02}.md) | {} | {} | {} |\n",
+            day.day,
+            escape(&day.title),
+            day.day,
+            solvers,
+            answer(1),
+            answer(2)
+        ));
+    }
+    if answers.is_empty() {
+        index.push_str("\nRun the solutions first to record the answers in the index.\n");
+    }
+    fs::write(format!("{}/README.md", DOCS_DIR), index)
+        .expect("Something went wrong writing the docs");
+}
+
+fn main() {
+    fs::create_dir_all(DOCS_DIR).expect("Something went wrong creating the docs directory");
+
+    let mut days = Vec::<Day>::new();
+    for day in 1..=25 {
+        if let Ok(source) = fs::read_to_string(format!("{}/d{:02}.rs", DAYS_DIR, day)) {
+            days.push(parse_day(day, &source));
+        }
+    }
+
+    days.iter().for_each(write_day);
+    write_index(&days);
+    println!("Extracted {} days into {}/", days.len(), DOCS_DIR);
+}
@@ -0,0 +1,66 @@
+use advent_of_code_2021::days::d06::{parse_timers, Population};
+use advent_of_code_2021::utils::cli::{self, exit_with, Args};
+use advent_of_code_2021::utils::read_file_lines;
+use num_bigint::BigUint;
+
+const USAGE: &str = "Usage: lanternfish [FILE] [--days D] [--modulus M] [--cycle C] \
+                     [--newborn-delay N] [--csv OUT] [--above N]";
+
+// Projects the school far beyond the puzzle's 256 days (10^18 days need a modulus),
+// along with the rate at which it eventually grows. --csv and --above simulate the
+// school day by day instead: a CSV only goes a few hundred days, until the counts
+// overflow.
+fn main() {
+    let mut filename = "input/06.txt".to_string();
+    let mut days = 256;
+    let mut modulus: Option<BigUint> = None;
+    let (mut cycle, mut newborn_delay) = (7, 9);
+    let (mut csv, mut above): (Option<String>, Option<u64>) = (None, None);
+
+    let mut args = Args::new(USAGE);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--days" => days = args.value(&arg),
+            "--modulus" => modulus = Some(args.value(&arg)),
+            "--cycle" => cycle = args.value(&arg),
+            "--newborn-delay" => newborn_delay = args.value(&arg),
+            "--csv" => csv = Some(args.value(&arg)),
+            "--above" => above = Some(args.value(&arg)),
+            _ => filename = args.file(arg),
+        }
+    }
+    if cycle < 1 || newborn_delay < 1 || modulus == Some(BigUint::default()) {
+        args.fail("--cycle, --newborn-delay and --modulus must be positive");
+    }
+
+    let timers = parse_timers(&read_file_lines(&filename));
+    let population = Population::new(cycle, newborn_delay);
+
+    if csv.is_some() || above.is_some() {
+        if let Some(out) = &csv {
+            match population.history(&timers, days) {
+                Ok(history) => cli::write(out, history.to_csv()),
+                Err(overflow) => exit_with(format!("{}: {}", filename, overflow)),
+            }
+        }
+        if let Some(count) = above {
+            match population.first_day_above(&timers, count, days) {
+                Some(day) => println!("More than {} lanternfish on day {}", count, day),
+                None => println!("At most {} lanternfish for {} days", count, days),
+            }
+        }
+        return;
+    }
+
+    let count = population.project(&timers, days, modulus.as_ref());
+    match &modulus {
+        Some(m) => println!("Lanternfish after {} days, modulo {}: {}", days, m, count),
+        None => println!("Lanternfish after {} days: {}", days, count),
+    }
+    println!(
+        "Growth rate: {:.6} per day, {:.4} per cycle of {} days",
+        population.growth_rate(),
+        population.growth_rate().powi(cycle as i32),
+        cycle
+    );
+}
@@ -0,0 +1,35 @@
+use advent_of_code_2021::days::d01::{depth_profile, parse_measurements};
+use advent_of_code_2021::utils::cli::Args;
+use advent_of_code_2021::utils::read_file_lines;
+
+const USAGE: &str = "Usage: sonar_report [FILE] [--json] [--window N] [--top N] [--z-threshold X]";
+
+fn main() {
+    let mut filename = "input/01.txt".to_string();
+    let mut json = false;
+    let mut window = 10;
+    let mut top = 5;
+    let mut z_threshold = 3.0;
+
+    let mut args = Args::new(USAGE);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--window" => window = args.value(&arg),
+            "--top" => top = args.value(&arg),
+            "--z-threshold" => z_threshold = args.value(&arg),
+            _ => filename = args.file(arg),
+        }
+    }
+    if window == 0 {
+        args.fail("--window must be at least 1");
+    }
+
+    let depths = parse_measurements(&read_file_lines(&filename));
+    let profile = depth_profile(&depths, window, top, z_threshold);
+    if json {
+        println!("{}", profile.to_json());
+    } else {
+        println!("{}", profile.to_text());
+    }
+}
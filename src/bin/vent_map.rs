@@ -0,0 +1,105 @@
+use advent_of_code_2021::days::d05::{
+    count_covered, parse_lines, Filter, Line, Point, Raster, VentMap,
+};
+use advent_of_code_2021::utils::cli::{self, exit_with, Args};
+use advent_of_code_2021::utils::read_file_lines;
+
+const USAGE: &str = "Usage: vent_map [FILE] [--lines orthogonal|diagonal|any] \
+                     [--raster exact|bresenham] [--min X,Y] [--max X,Y] [--at X,Y] [--pgm OUT] \
+                     [--dims 2|3] [--k K]";
+
+fn point(args: &mut Args, flag: &str) -> Point {
+    let coords = args
+        .value::<String>(flag)
+        .split(',')
+        .map(str::parse)
+        .collect::<Result<Vec<i32>, _>>();
+    match coords.as_deref() {
+        Ok([x, y]) => Point::new(*x, *y),
+        _ => args.fail(format!("{} expects X,Y", flag)),
+    }
+}
+
+// Prints the diagram of the field (or of [--min, --max]), or the lines covering
+// the point given with --at. --k K adds the number of points covered by at least
+// K lines, which is all 3D fields (--dims 3) get.
+fn main() {
+    let mut filename = "input/05.txt".to_string();
+    let mut filter = Filter::Any;
+    let mut raster = Raster::Exact;
+    let (mut min, mut max, mut at, mut pgm) = (None, None, None, None);
+    let (mut dims, mut k) = (2, None);
+
+    let mut args = Args::new(USAGE);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--lines" => filter = args.value(&arg),
+            "--raster" => raster = args.value(&arg),
+            "--min" => min = Some(point(&mut args, &arg)),
+            "--max" => max = Some(point(&mut args, &arg)),
+            "--at" => at = Some(point(&mut args, &arg)),
+            "--pgm" => pgm = Some(args.value::<String>(&arg)),
+            "--dims" => dims = args.value(&arg),
+            "--k" => k = Some(args.value::<u32>(&arg)),
+            _ => filename = args.file(arg),
+        }
+    }
+
+    if k == Some(0) {
+        args.fail("--k must be at least 1");
+    }
+    let input = read_file_lines(&filename);
+    match dims {
+        2 => {}
+        3 => {
+            let lines: Vec<Line<3>> = parse_lines(&input);
+            println!("{}", count_covered(&lines, k.unwrap_or(2), filter, raster));
+            return;
+        }
+        _ => args.fail("--dims must be 2 or 3"),
+    }
+    let lines: Vec<Line> = parse_lines(&input);
+    let map = VentMap::new(&lines, filter, raster);
+    if let Some(point) = at {
+        if k.is_some() {
+            args.fail("--k doesn't apply to --at");
+        }
+        for i in map.lines_at(point) {
+            let line = lines[i];
+            println!(
+                "line {}: {},{} -> {},{}",
+                i + 1,
+                line.from.x(),
+                line.from.y(),
+                line.to.x(),
+                line.to.y()
+            );
+        }
+        println!(
+            "{} lines cover {},{}",
+            map.coverage(point),
+            point.x(),
+            point.y()
+        );
+        return;
+    }
+
+    let (min, max) = match (map.bounds(), min, max) {
+        (_, Some(min), Some(max)) => (min, max),
+        (Some((low, high)), min, max) => (min.unwrap_or(low), max.unwrap_or(high)),
+        (None, _, _) => exit_with(format!("{}: no lines to draw", filename)),
+    };
+    if min.x() > max.x() || min.y() > max.y() {
+        args.fail("--min must be above and left of --max");
+    }
+    let (overlap, point) = map.max_in(min, max);
+    match pgm {
+        Some(out) => cli::write(&out, map.heatmap_pgm(min, max)),
+        None => println!("{}", map.diagram(min, max)),
+    }
+    eprintln!("Max overlap {} at {},{}", overlap, point.x(), point.y());
+    if let Some(k) = k {
+        let covered = count_covered(&lines, k, filter, raster);
+        eprintln!("{} points covered by at least {} lines", covered, k);
+    }
+}
@@ -1,4 +1,7 @@
 use crate::utils::read_file_lines;
+use crate::utils::report::answer;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
 
 /*
 As the submarine drops below the surface of the ocean, it automatically performs a sonar sweep of the nearby sea floor. On a small screen, the sonar sweep report (your puzzle input) appears: each line is a measurement of the sea floor depth as the sweep looks further and further away from the submarine.
@@ -38,22 +41,348 @@ In this example, there are 7 measurements that are larger than the previous meas
 How many measurements are larger than the previous measurement?
 */
 
-pub fn part1() {
-    let mut prev: i16 = i16::MAX;
-    let count = read_file_lines("input/01.txt")
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Increased,
+    Decreased,
+    Unchanged,
+}
+
+impl From<Ordering> for Trend {
+    fn from(ordering: Ordering) -> Self {
+        match ordering {
+            Ordering::Greater => Trend::Increased,
+            Ordering::Less => Trend::Decreased,
+            Ordering::Equal => Trend::Unchanged,
+        }
+    }
+}
+
+// Compares the sum of each `size`-measurement window with the previous one.
+// `compare` receives (current sum, previous sum).
+pub struct WindowTrends<I, F> {
+    depths: I,
+    size: usize,
+    window: VecDeque<i64>,
+    sum: i64,
+    previous: Option<i64>,
+    compare: F,
+}
+
+impl<I, F> Iterator for WindowTrends<I, F>
+where
+    I: Iterator<Item = i64>,
+    F: FnMut(i64, i64) -> Ordering,
+{
+    type Item = Trend;
+
+    fn next(&mut self) -> Option<Trend> {
+        for depth in self.depths.by_ref() {
+            self.window.push_back(depth);
+            self.sum += depth;
+            if self.window.len() > self.size {
+                self.sum -= self.window.pop_front().unwrap();
+            }
+            if self.window.len() < self.size {
+                continue;
+            }
+            let previous = self.previous.replace(self.sum);
+            if let Some(previous) = previous {
+                return Some((self.compare)(self.sum, previous).into());
+            }
+        }
+        None
+    }
+}
+
+pub trait SlidingWindows: Iterator<Item = i64> + Sized {
+    fn window_trends<F>(self, size: usize, compare: F) -> WindowTrends<Self, F>
+    where
+        F: FnMut(i64, i64) -> Ordering,
+    {
+        assert!(size > 0, "Window size must be at least 1");
+        WindowTrends {
+            depths: self,
+            size,
+            window: VecDeque::with_capacity(size + 1),
+            sum: 0,
+            previous: None,
+            compare,
+        }
+    }
+}
+
+impl<I: Iterator<Item = i64>> SlidingWindows for I {}
+
+pub fn parse_measurements(lines: &[String]) -> Vec<i64> {
+    lines.iter().map(|x| x.parse().unwrap()).collect()
+}
+
+#[derive(Clone, Copy)]
+pub struct Run {
+    pub start: usize,
+    pub length: usize,
+    pub trend: Trend,
+}
+
+pub struct Jump {
+    pub index: usize,
+    pub from: i64,
+    pub to: i64,
+}
+
+pub struct Rolling {
+    pub index: usize,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+pub struct Outlier {
+    pub index: usize,
+    pub depth: i64,
+    pub z_score: f64,
+}
+
+pub struct DepthProfile {
+    pub readings: usize,
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    pub window: usize,
+    pub longest_run: Option<Run>,
+    pub largest_jumps: Vec<Jump>,
+    pub rolling: Vec<Rolling>,
+    pub outliers: Vec<Outlier>,
+}
+
+fn longest_run(depths: &[i64]) -> Option<Run> {
+    let mut longest: Option<Run> = None;
+    let mut current: Option<Run> = None;
+    let trends = depths
         .iter()
-        .fold(0, |count, measurement_str| {
-            let measurement = measurement_str.parse().unwrap();
-            let increase = measurement > prev;
-            prev = measurement;
-            if increase {
-                count + 1
+        .copied()
+        .window_trends(1, |current, previous| current.cmp(&previous));
+    for (i, trend) in trends.enumerate() {
+        current = match current {
+            Some(run) if run.trend == trend => Some(Run {
+                length: run.length + 1,
+                ..run
+            }),
+            _ if trend == Trend::Unchanged => None,
+            _ => Some(Run {
+                start: i,
+                length: 2,
+                trend,
+            }),
+        };
+        match (&longest, &current) {
+            (Some(l), Some(run)) if run.length > l.length => longest = current,
+            (None, Some(_)) => longest = current,
+            _ => {}
+        }
+    }
+    longest
+}
+
+fn largest_jumps(depths: &[i64], top: usize) -> Vec<Jump> {
+    let mut jumps = depths
+        .windows(2)
+        .enumerate()
+        .map(|(i, pair)| Jump {
+            index: i + 1,
+            from: pair[0],
+            to: pair[1],
+        })
+        .collect::<Vec<Jump>>();
+    jumps.sort_by_key(|jump| std::cmp::Reverse((jump.to as i128 - jump.from as i128).abs()));
+    jumps.truncate(top);
+    jumps
+}
+
+// Mean and (population) standard deviation of every `window`-reading stretch. The
+// sum slides along in i128, so the mean is exact for any depths; the variance is
+// then summed over the window's deviations from that mean. A sliding sum of squares
+// would be O(1) per reading but cancels catastrophically: readings alternating
+// between 1000000000 and 1000000001 would get a standard deviation of 0.
+fn rolling_stats(depths: &[i64], window: usize) -> Vec<Rolling> {
+    let mut rolling = Vec::<Rolling>::new();
+    let mut sum: i128 = 0;
+    for (i, depth) in depths.iter().enumerate() {
+        sum += *depth as i128;
+        if i >= window {
+            sum -= depths[i - window] as i128;
+        }
+        if i + 1 >= window {
+            let n = window as f64;
+            let mean = sum as f64 / n;
+            let variance = depths[i + 1 - window..=i]
+                .iter()
+                .map(|depth| (*depth as f64 - mean).powi(2))
+                .sum::<f64>()
+                / n;
+            rolling.push(Rolling {
+                index: i,
+                mean,
+                std_dev: variance.sqrt(),
+            });
+        }
+    }
+    rolling
+}
+
+// Each reading is scored against the window of readings just before it.
+fn outliers(depths: &[i64], rolling: &[Rolling], z_threshold: f64) -> Vec<Outlier> {
+    rolling
+        .iter()
+        .filter(|stats| stats.std_dev > 0.0 && stats.index + 1 < depths.len())
+        .filter_map(|stats| {
+            let index = stats.index + 1;
+            let z_score = (depths[index] as f64 - stats.mean) / stats.std_dev;
+            if z_score.abs() >= z_threshold {
+                Some(Outlier {
+                    index,
+                    depth: depths[index],
+                    z_score,
+                })
             } else {
-                count
+                None
             }
+        })
+        .collect()
+}
+
+pub fn depth_profile(depths: &[i64], window: usize, top: usize, z_threshold: f64) -> DepthProfile {
+    assert!(window > 0, "Window size must be at least 1");
+    let rolling = rolling_stats(depths, window);
+    DepthProfile {
+        readings: depths.len(),
+        min: depths.iter().min().copied(),
+        max: depths.iter().max().copied(),
+        window,
+        longest_run: longest_run(depths),
+        largest_jumps: largest_jumps(depths, top),
+        outliers: outliers(depths, &rolling, z_threshold),
+        rolling,
+    }
+}
+
+fn trend_name(trend: Trend) -> &'static str {
+    match trend {
+        Trend::Increased => "increasing",
+        Trend::Decreased => "decreasing",
+        Trend::Unchanged => "unchanged",
+    }
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map_or("null".to_string(), |v| v.to_string())
+}
+
+impl DepthProfile {
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![
+            format!("Readings: {}", self.readings),
+            format!("Min depth: {}", optional(self.min)),
+            format!("Max depth: {}", optional(self.max)),
+        ];
+        match &self.longest_run {
+            Some(run) => lines.push(format!(
+                "Longest monotonic run: {} readings {} from index {}",
+                run.length,
+                trend_name(run.trend),
+                run.start
+            )),
+            None => lines.push("Longest monotonic run: none".to_string()),
+        }
+        lines.push("Largest jumps:".to_string());
+        self.largest_jumps.iter().for_each(|jump| {
+            lines.push(format!(
+                "  index {}: {} -> {} ({:+})",
+                jump.index,
+                jump.from,
+                jump.to,
+                jump.to as i128 - jump.from as i128
+            ))
         });
+        if let Some(last) = self.rolling.last() {
+            lines.push(format!(
+                "Rolling {}-reading window (last): mean {:.3}, std dev {:.3}",
+                self.window, last.mean, last.std_dev
+            ));
+        }
+        lines.push(format!("Outliers: {}", self.outliers.len()));
+        self.outliers.iter().for_each(|outlier| {
+            lines.push(format!(
+                "  index {}: depth {} (z = {:.2})",
+                outlier.index, outlier.depth, outlier.z_score
+            ))
+        });
+        lines.join("\n")
+    }
 
-    println!("Day 01 > Part 1: {}", count);
+    pub fn to_json(&self) -> String {
+        let longest_run = match &self.longest_run {
+            Some(run) => format!(
+                "{{\"start\": {}, \"length\": {}, \"trend\": \"{}\"}}",
+                run.start,
+                run.length,
+                trend_name(run.trend)
+            ),
+            None => "null".to_string(),
+        };
+        let jumps = self
+            .largest_jumps
+            .iter()
+            .map(|jump| {
+                format!(
+                    "{{\"index\": {}, \"from\": {}, \"to\": {}}}",
+                    jump.index, jump.from, jump.to
+                )
+            })
+            .collect::<Vec<String>>();
+        let rolling = self
+            .rolling
+            .iter()
+            .map(|stats| {
+                format!(
+                    "{{\"index\": {}, \"mean\": {:.3}, \"std_dev\": {:.3}}}",
+                    stats.index, stats.mean, stats.std_dev
+                )
+            })
+            .collect::<Vec<String>>();
+        let outliers = self
+            .outliers
+            .iter()
+            .map(|outlier| {
+                format!(
+                    "{{\"index\": {}, \"depth\": {}, \"z_score\": {:.3}}}",
+                    outlier.index, outlier.depth, outlier.z_score
+                )
+            })
+            .collect::<Vec<String>>();
+        format!(
+            "{{\"readings\": {}, \"min\": {}, \"max\": {}, \"window\": {}, \"longest_run\": {}, \"largest_jumps\": [{}], \"rolling\": [{}], \"outliers\": [{}]}}",
+            self.readings,
+            optional(self.min),
+            optional(self.max),
+            self.window,
+            longest_run,
+            jumps.join(", "),
+            rolling.join(", "),
+            outliers.join(", ")
+        )
+    }
+}
+
+fn count_increases(size: usize) -> usize {
+    parse_measurements(&read_file_lines("input/01.txt"))
+        .into_iter()
+        .window_trends(size, |current, previous| current.cmp(&previous))
+        .filter(|trend| *trend == Trend::Increased)
+        .count()
+}
+
+pub fn part1() {
+    answer(1, 1, count_increases(1));
 }
 
 /*
@@ -93,47 +422,133 @@ Consider sums of a three-measurement sliding window. How many sums are larger th
 */
 
 pub fn part2() {
-    let measurements: Vec<i16> = read_file_lines("input/01.txt")
-        .iter()
-        .map(|x| x.parse().unwrap())
-        .collect();
+    answer(1, 2, count_increases(3));
+}
 
-    let mut window_1: i16 = 0;
-    let mut window_2: i16 = 0;
-    let mut count: i16 = 0;
-    let mut index: usize = 0;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
 
-    for measurement in &measurements {
-        if index < 3 {
-            window_1 += measurement;
-        }
-        if (1..4).contains(&index) {
-            window_2 += measurement;
-        }
+    const EXAMPLE: [i64; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
 
-        if index < 4 {
-            index += 1;
-            continue;
+    #[test]
+    fn example_trends() {
+        let increases = |size| {
+            EXAMPLE
+                .into_iter()
+                .window_trends(size, |current, previous| current.cmp(&previous))
+                .filter(|trend| *trend == Trend::Increased)
+                .count()
         };
+        assert_eq!(increases(1), 7);
+        assert_eq!(increases(3), 5);
 
-        if window_1 < window_2 {
-            count += 1;
-        }
+        let sums = EXAMPLE
+            .into_iter()
+            .window_trends(3, |current, previous| current.cmp(&previous))
+            .collect::<Vec<Trend>>();
+        use Trend::*;
+        assert_eq!(
+            sums,
+            [Increased, Unchanged, Decreased, Increased, Increased, Increased, Increased]
+        );
+    }
 
-        let window_1_start = measurements[index - 4];
-        let window_2_start = measurements[index - 3];
-        let window_2_end = measurements[index - 1];
-        window_1 -= window_1_start;
-        window_1 += window_2_end;
-        window_2 -= window_2_start;
-        window_2 += measurement;
+    #[test]
+    fn runs_jumps_and_outliers() {
+        let depths = [10, 11, 12, 13, 9, 9, 8, 30, 31];
+        let profile = depth_profile(&depths, 3, 2, 2.0);
+        let run = profile.longest_run.unwrap();
+        assert_eq!((run.start, run.length, run.trend), (0, 4, Trend::Increased));
+        let jumps = profile
+            .largest_jumps
+            .iter()
+            .map(|jump| (jump.index, jump.from, jump.to))
+            .collect::<Vec<_>>();
+        assert_eq!(jumps, [(7, 8, 30), (4, 13, 9)]);
+        let outliers = profile
+            .outliers
+            .iter()
+            .map(|outlier| (outlier.index, outlier.depth))
+            .collect::<Vec<_>>();
+        assert_eq!(outliers, [(3, 13), (4, 9), (7, 30)]);
+        assert!((profile.outliers[0].z_score - 6_f64.sqrt()).abs() < 1e-9);
+        assert_eq!(
+            profile.to_text(),
+            "Readings: 9\n\
+             Min depth: 8\n\
+             Max depth: 31\n\
+             Longest monotonic run: 4 readings increasing from index 0\n\
+             Largest jumps:\n  \
+               index 7: 8 -> 30 (+22)\n  \
+               index 4: 13 -> 9 (-4)\n\
+             Rolling 3-reading window (last): mean 23.000, std dev 10.614\n\
+             Outliers: 3\n  \
+               index 3: depth 13 (z = 2.45)\n  \
+               index 4: depth 9 (z = -3.67)\n  \
+               index 7: depth 30 (z = 45.25)"
+        );
+    }
 
-        index += 1;
+    #[test]
+    fn profiles_as_json() {
+        assert_eq!(
+            depth_profile(&[1, 3, 2], 2, 1, 1.0).to_json(),
+            "{\"readings\": 3, \"min\": 1, \"max\": 3, \"window\": 2, \
+             \"longest_run\": {\"start\": 0, \"length\": 2, \"trend\": \"increasing\"}, \
+             \"largest_jumps\": [{\"index\": 1, \"from\": 1, \"to\": 3}], \
+             \"rolling\": [{\"index\": 1, \"mean\": 2.000, \"std_dev\": 1.000}, \
+             {\"index\": 2, \"mean\": 2.500, \"std_dev\": 0.500}], \"outliers\": []}"
+        );
+        assert_eq!(
+            depth_profile(&[], 2, 1, 1.0).to_json(),
+            "{\"readings\": 0, \"min\": null, \"max\": null, \"window\": 2, \
+             \"longest_run\": null, \"largest_jumps\": [], \"rolling\": [], \"outliers\": []}"
+        );
     }
 
-    if window_1 < window_2 {
-        count += 1;
+    // Sonar readings around some depth, possibly very deep.
+    fn sweep() -> impl Strategy<Value = (Vec<i64>, i64)> {
+        (
+            prop::collection::vec(-1000_i64..1000, 1..40),
+            prop_oneof![Just(0_i64), 0_i64..1_000_000_000_000],
+        )
     }
 
-    println!("Day 01 > Part 2: {}", count);
+    fn window_sums(depths: &[i64], size: usize) -> Vec<i64> {
+        depths
+            .windows(size)
+            .map(|window| window.iter().sum())
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn window_trends_compare_window_sums((depths, _) in sweep(), size in 1_usize..5) {
+            let trends = depths.iter().copied().window_trends(size, |a, b| a.cmp(&b)).collect::<Vec<_>>();
+            let sums = window_sums(&depths, size);
+            let expected = sums
+                .windows(2)
+                .map(|pair| Trend::from(pair[1].cmp(&pair[0])))
+                .collect::<Vec<_>>();
+            prop_assert_eq!(trends, expected);
+        }
+
+        #[test]
+        fn rolling_stats_ignore_the_depth_offset((depths, offset) in sweep(), window in 1_usize..8) {
+            let deep = depths.iter().map(|depth| depth + offset).collect::<Vec<i64>>();
+            let shallow = depth_profile(&depths, window, 0, 3.0).rolling;
+            let deep = depth_profile(&deep, window, 0, 3.0).rolling;
+            prop_assert_eq!(shallow.len(), deep.len());
+            for (shallow, deep) in shallow.iter().zip(&deep) {
+                prop_assert!((deep.mean - offset as f64 - shallow.mean).abs() < 1e-3);
+                prop_assert!((deep.std_dev - shallow.std_dev).abs() < 1e-3);
+            }
+            let alternating = (0..window.max(2) * 3).map(|i| 1_000_000_000 + i as i64 % 2).collect::<Vec<i64>>();
+            for stats in depth_profile(&alternating, 2, 0, 3.0).rolling {
+                prop_assert_eq!(stats.std_dev, 0.5);
+            }
+        }
+    }
 }
@@ -1,4 +1,8 @@
 use crate::utils::read_file_lines;
+use crate::utils::report::answer;
+use std::fmt;
+
+pub mod script;
 
 /*
 Now, you need to figure out how to pilot this thing.
@@ -34,33 +38,328 @@ After following these instructions, you would have a horizontal position of 15 a
 Calculate the horizontal position and depth you would have after following the planned course. What do you get if you multiply your final horizontal position by your final depth?
 */
 
-pub fn part1() {
-    let mut hor_pos: i32 = 0;
-    let mut depth_pos: i32 = 0;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Forward(i64),
+    Down(i64),
+    Up(i64),
+}
 
-    read_file_lines("input/02.txt")
-        .iter()
-        .for_each(|instruction| {
-            let mut chunks = instruction.split_whitespace();
-            let instruction = chunks.next().unwrap();
-            let amount: i32 = chunks.next().unwrap().parse().unwrap();
-            match instruction {
-                "forward" => {
-                    hor_pos += amount;
-                }
-                "down" => {
-                    depth_pos += amount;
-                }
-                "up" => {
-                    depth_pos -= amount;
-                }
-                _ => {
-                    println!("Weird instruction found")
-                }
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    UnknownVerb { line: usize, verb: String },
+    MissingAmount { line: usize, verb: String },
+    InvalidAmount { line: usize, amount: String },
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CommandError::UnknownVerb { line, verb } => {
+                write!(f, "line {}: unknown command `{}`", line, verb)
+            }
+            CommandError::MissingAmount { line, verb } => {
+                write!(f, "line {}: `{}` is missing an amount", line, verb)
             }
+            CommandError::InvalidAmount { line, amount } => {
+                write!(f, "line {}: `{}` is not a valid amount", line, amount)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+// `line` is only used for error messages (1-based).
+pub fn parse_command(line: usize, text: &str) -> Result<Command, CommandError> {
+    let mut chunks = text.split_whitespace();
+    let verb = chunks.next().unwrap_or_default().to_string();
+    let command: fn(i64) -> Command = match verb.as_str() {
+        "forward" => Command::Forward,
+        "down" => Command::Down,
+        "up" => Command::Up,
+        _ => return Err(CommandError::UnknownVerb { line, verb }),
+    };
+    let amount = chunks
+        .next()
+        .ok_or(CommandError::MissingAmount { line, verb })?;
+    amount
+        .parse()
+        .map(command)
+        .map_err(|_| CommandError::InvalidAmount {
+            line,
+            amount: amount.to_string(),
+        })
+}
+
+pub fn parse_commands(lines: &[String]) -> Result<Vec<Command>, CommandError> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse_command(i + 1, line))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Position {
+    pub horizontal: i64,
+    pub depth: i64,
+    pub aim: i64,
+}
+
+pub trait NavigationModel {
+    fn apply(&self, position: Position, command: Command) -> Position;
+
+    // `None` if the new position doesn't fit in an i64. Scripts go through this, so
+    // models that can overflow should override it.
+    fn checked_apply(&self, position: Position, command: Command) -> Option<Position> {
+        Some(self.apply(position, command))
+    }
+}
+
+// Part 1: `down`/`up` change the depth directly.
+pub struct Plain;
+
+impl NavigationModel for Plain {
+    fn apply(&self, position: Position, command: Command) -> Position {
+        self.checked_apply(position, command)
+            .expect("Position overflows i64")
+    }
+
+    fn checked_apply(&self, position: Position, command: Command) -> Option<Position> {
+        Some(match command {
+            Command::Forward(amount) => Position {
+                horizontal: position.horizontal.checked_add(amount)?,
+                ..position
+            },
+            Command::Down(amount) => Position {
+                depth: position.depth.checked_add(amount)?,
+                ..position
+            },
+            Command::Up(amount) => Position {
+                depth: position.depth.checked_sub(amount)?,
+                ..position
+            },
+        })
+    }
+}
+
+// Part 2: `down`/`up` change the aim, and `forward` dives along it.
+pub struct Aimed;
+
+impl NavigationModel for Aimed {
+    fn apply(&self, position: Position, command: Command) -> Position {
+        self.checked_apply(position, command)
+            .expect("Position overflows i64")
+    }
+
+    fn checked_apply(&self, position: Position, command: Command) -> Option<Position> {
+        Some(match command {
+            Command::Forward(amount) => Position {
+                horizontal: position.horizontal.checked_add(amount)?,
+                depth: position
+                    .depth
+                    .checked_add(amount.checked_mul(position.aim)?)?,
+                ..position
+            },
+            Command::Down(amount) => Position {
+                aim: position.aim.checked_add(amount)?,
+                ..position
+            },
+            Command::Up(amount) => Position {
+                aim: position.aim.checked_sub(amount)?,
+                ..position
+            },
+        })
+    }
+}
+
+// User-defined models can be plain closures.
+impl<F: Fn(Position, Command) -> Position> NavigationModel for F {
+    fn apply(&self, position: Position, command: Command) -> Position {
+        self(position, command)
+    }
+}
+
+pub struct Submarine<M> {
+    model: M,
+    history: Vec<Position>,
+}
+
+impl<M: NavigationModel> Submarine<M> {
+    pub fn new(model: M) -> Self {
+        Submarine {
+            model,
+            history: vec![Position::default()],
+        }
+    }
+
+    pub fn position(&self) -> Position {
+        *self.history.last().unwrap()
+    }
+
+    // Every position visited so far, starting at the origin.
+    pub fn history(&self) -> &[Position] {
+        &self.history
+    }
+
+    pub fn execute(&mut self, command: Command) -> Position {
+        let position = self.model.apply(self.position(), command);
+        self.history.push(position);
+        position
+    }
+
+    // Like `execute`, but leaves the submarine in place if the move overflows.
+    pub fn try_execute(&mut self, command: Command) -> Option<Position> {
+        let position = self.model.checked_apply(self.position(), command)?;
+        self.history.push(position);
+        Some(position)
+    }
+
+    pub fn run<I: IntoIterator<Item = Command>>(&mut self, commands: I) -> Position {
+        commands.into_iter().for_each(|command| {
+            self.execute(command);
+        });
+        self.position()
+    }
+}
+
+pub fn navigate<M: NavigationModel>(model: M, commands: &[Command]) -> (Position, Vec<Position>) {
+    let mut submarine = Submarine::new(model);
+    let position = submarine.run(commands.iter().copied());
+    (position, submarine.history)
+}
+
+// Names with a comma, a quote or a line break are quoted, as in RFC 4180.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn xml_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+// One row per visited position of every named trajectory.
+pub fn trajectory_csv(trajectories: &[(&str, &[Position])]) -> String {
+    let mut csv = String::from("model,step,horizontal,depth,aim\n");
+    for (name, history) in trajectories {
+        let name = csv_field(name);
+        for (step, position) in history.iter().enumerate() {
+            csv.push_str(&format!(
+                "{},{},{},{},{}\n",
+                name, step, position.horizontal, position.depth, position.aim
+            ));
+        }
+    }
+    csv
+}
+
+const PLOT_WIDTH: i64 = 800;
+const PANEL_HEIGHT: i64 = 260;
+const PLOT_MARGIN: i64 = 50;
+const PLOT_COLORS: [&str; 4] = ["#1f77b4", "#d62728", "#2ca02c", "#9467bd"];
+
+// Depth against horizontal distance, one panel per trajectory. The panels share the
+// distance axis but each gets its own depth scale: part 2 dives orders of magnitude
+// deeper than part 1, so a common scale would flatten the part 1 curve.
+pub fn depth_plot_svg(trajectories: &[(&str, &[Position])]) -> String {
+    // `forward` can be negative, so the shared axis spans the leftmost to the
+    // rightmost position (and always the origin).
+    let horizontals = trajectories
+        .iter()
+        .flat_map(|(_, history)| history.iter().map(|p| p.horizontal))
+        .chain(std::iter::once(0));
+    let (min_horizontal, max_horizontal) = horizontals
+        .fold((i64::MAX, i64::MIN), |(min, max), h| {
+            (min.min(h), max.max(h))
+        });
+    // Spans are taken in f64: they don't fit in an i64 for extreme positions.
+    let width_span = (max_horizontal as f64 - min_horizontal as f64).max(1.0);
+    let inner_width = PLOT_WIDTH - 2 * PLOT_MARGIN;
+    let inner_height = PANEL_HEIGHT - 2 * PLOT_MARGIN;
+    let height = PANEL_HEIGHT * trajectories.len().max(1) as i64;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"monospace\" font-size=\"12\">\n",
+        PLOT_WIDTH, height
+    );
+    svg.push_str(&format!(
+        "<rect width=\"{}\" height=\"{}\" fill=\"white\"/>\n",
+        PLOT_WIDTH, height
+    ));
+    for (i, (name, history)) in trajectories.iter().enumerate() {
+        let top = i as i64 * PANEL_HEIGHT + PLOT_MARGIN;
+        let depths = history.iter().map(|p| p.depth).chain(std::iter::once(0));
+        let (min_depth, max_depth) = depths.fold((i64::MAX, i64::MIN), |(min, max), d| {
+            (min.min(d), max.max(d))
         });
+        let span = (max_depth as f64 - min_depth as f64).max(1.0);
+        let x = |horizontal: i64| {
+            PLOT_MARGIN as f64
+                + (horizontal as f64 - min_horizontal as f64) * inner_width as f64 / width_span
+        };
+        // Depth grows downwards, like the submarine.
+        let y = |depth: i64| {
+            top as f64 + (depth as f64 - min_depth as f64) * inner_height as f64 / span
+        };
 
-    println!("Day 02 > Part 1: {}", hor_pos * depth_pos);
+        svg.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"#999\"/>\n",
+            PLOT_MARGIN, top, inner_width, inner_height
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\">{}</text>\n",
+            PLOT_MARGIN,
+            top - 10,
+            xml_text(name)
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>\n<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>\n",
+            PLOT_MARGIN - 5,
+            top + 4,
+            min_depth,
+            PLOT_MARGIN - 5,
+            top + inner_height + 4,
+            max_depth
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\">horizontal {}</text>\n<text x=\"{}\" y=\"{}\" text-anchor=\"end\">horizontal {}</text>\n",
+            PLOT_MARGIN,
+            top + inner_height + 20,
+            min_horizontal,
+            PLOT_MARGIN + inner_width,
+            top + inner_height + 20,
+            max_horizontal
+        ));
+        let points = history
+            .iter()
+            .map(|p| format!("{:.1},{:.1}", x(p.horizontal), y(p.depth)))
+            .collect::<Vec<String>>();
+        svg.push_str(&format!(
+            "<polyline fill=\"none\" stroke=\"{}\" stroke-width=\"1.5\" points=\"{}\"/>\n",
+            PLOT_COLORS[i % PLOT_COLORS.len()],
+            points.join(" ")
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn planned_course() -> Vec<Command> {
+    parse_commands(&read_file_lines("input/02.txt")).unwrap_or_else(|error| panic!("{}", error))
+}
+
+pub fn part1() {
+    let (position, _history) = navigate(Plain, &planned_course());
+
+    answer(2, 1, position.horizontal * position.depth);
 }
 
 /*
@@ -91,32 +390,90 @@ Using this new interpretation of the commands, calculate the horizontal position
 */
 
 pub fn part2() {
-    let mut hor_pos: i32 = 0;
-    let mut depth_pos: i32 = 0;
-    let mut aim: i32 = 0;
+    let (position, _history) = navigate(Aimed, &planned_course());
 
-    read_file_lines("input/02.txt")
-        .iter()
-        .for_each(|instruction| {
-            let mut chunks = instruction.split_whitespace();
-            let instruction = chunks.next().unwrap();
-            let amount: i32 = chunks.next().unwrap().parse().unwrap();
-            match instruction {
-                "forward" => {
-                    hor_pos += amount;
-                    depth_pos += amount * aim;
-                }
-                "down" => {
-                    aim += amount;
-                }
-                "up" => {
-                    aim -= amount;
-                }
-                _ => {
-                    println!("Weird instruction found")
-                }
-            }
-        });
+    answer(2, 2, position.horizontal * position.depth);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2";
+
+    fn example() -> Vec<Command> {
+        let lines = EXAMPLE.lines().map(String::from).collect::<Vec<String>>();
+        parse_commands(&lines).unwrap()
+    }
+
+    #[test]
+    fn example_course() {
+        let (position, history) = navigate(Plain, &example());
+        assert_eq!((position.horizontal, position.depth), (15, 10));
+        assert_eq!(history.len(), 7);
+
+        let (position, _) = navigate(Aimed, &example());
+        assert_eq!(
+            (position.horizontal, position.depth, position.aim),
+            (15, 60, 10)
+        );
+    }
+
+    #[test]
+    fn closures_are_navigation_models() {
+        let backwards = |position: Position, command: Command| match command {
+            Command::Forward(x) => Position {
+                horizontal: position.horizontal - x,
+                ..position
+            },
+            _ => Plain.apply(position, command),
+        };
+        let (position, _) = navigate(backwards, &example());
+        assert_eq!((position.horizontal, position.depth), (-15, 10));
+    }
+
+    #[test]
+    fn trajectories_as_csv() {
+        let (_, plain) = navigate(Plain, &example());
+        let (_, aimed) = navigate(Aimed, &example());
+        let csv = trajectory_csv(&[("plain", &plain), ("aimed, \"v2\"", &aimed)]);
+        let rows = csv.lines().collect::<Vec<&str>>();
+        assert_eq!(rows.len(), 1 + 7 + 7);
+        assert_eq!(rows[0], "model,step,horizontal,depth,aim");
+        assert_eq!(rows[1], "plain,0,0,0,0");
+        assert_eq!(rows[7], "plain,6,15,10,0");
+        assert_eq!(rows[14], "\"aimed, \"\"v2\"\"\",6,15,60,10");
+    }
+
+    #[test]
+    fn depth_plots_have_a_panel_per_trajectory() {
+        let (_, plain) = navigate(Plain, &example());
+        let (_, aimed) = navigate(Aimed, &example());
+        let svg = depth_plot_svg(&[("plain", &plain), ("<aimed> & co", &aimed)]);
+        assert!(svg
+            .starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"800\" height=\"520\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert!(svg.contains(">&lt;aimed&gt; &amp; co</text>"));
+        assert!(!svg.contains("<aimed>"));
+        // Part 2 dives to 60 at horizontal 15, the bottom right corner of its panel.
+        assert!(svg.contains(" 750.0,470.0\"/>"));
+    }
 
-    println!("Day 02 > Part 2: {}", hor_pos * depth_pos);
+    #[test]
+    fn malformed_commands_name_their_line() {
+        let lines = ["forward 5", "", "sideways 3", "up"].map(String::from);
+        assert_eq!(
+            parse_commands(&lines).unwrap_err().to_string(),
+            "line 3: unknown command `sideways`"
+        );
+        assert_eq!(
+            parse_command(4, "up").unwrap_err().to_string(),
+            "line 4: `up` is missing an amount"
+        );
+        assert_eq!(
+            parse_command(1, "down x").unwrap_err().to_string(),
+            "line 1: `x` is not a valid amount"
+        );
+    }
 }
@@ -0,0 +1,757 @@
+// A small scripting language on top of the day 2 commands:
+//
+//     # comments run to the end of the line
+//     let speed = 5
+//     macro dive {
+//         down speed
+//         forward 2 * speed
+//     }
+//     repeat 3 {
+//         dive
+//         if depth > 100 { up 10 } else { forward 1 }
+//     }
+//
+// Plain command lists (the puzzle input) are valid scripts. Expressions support
+// `+`, `-`, `*`, parentheses, variables and the `horizontal`, `depth` and `aim`
+// of the submarine; conditions compare two expressions with `>`, `<`, `>=`, `<=`,
+// `==` or `!=`.
+use super::{Command, NavigationModel, Position, Submarine};
+use std::collections::HashMap;
+use std::fmt;
+
+const MAX_CALL_DEPTH: usize = 64;
+// Blocks, parentheses and operators inside one another, while parsing. Parsing and
+// evaluating recurse that deep, so deeper scripts would overflow the stack.
+const MAX_NESTING: usize = 256;
+// Statements executed and loop iterations run, together.
+const MAX_STEPS: usize = 10_000_000;
+const RESERVED: [&str; 11] = [
+    "forward",
+    "down",
+    "up",
+    "repeat",
+    "if",
+    "else",
+    "let",
+    "macro",
+    "horizontal",
+    "depth",
+    "aim",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+fn error<T, S: Into<String>>(line: usize, message: S) -> Result<T, ScriptError> {
+    Err(ScriptError {
+        line,
+        message: message.into(),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Number(i64),
+    Ident(String),
+    Symbol(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "`{}`", n),
+            Token::Ident(name) => write!(f, "`{}`", name),
+            Token::Symbol(symbol) => write!(f, "`{}`", symbol),
+        }
+    }
+}
+
+// Two-character symbols come first so that `>=` isn't lexed as `>` followed by `=`.
+const SYMBOLS: [&str; 14] = [
+    ">=", "<=", "==", "!=", "{", "}", "(", ")", "=", "+", "-", "*", ">", "<",
+];
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, ScriptError> {
+    let mut tokens = Vec::<(Token, usize)>::new();
+    for (i, text) in source.lines().enumerate() {
+        let line = i + 1;
+        let chars = text
+            .split('#')
+            .next()
+            .unwrap()
+            .chars()
+            .collect::<Vec<char>>();
+        let mut pos = 0;
+        while pos < chars.len() {
+            let c = chars[pos];
+            let start = pos;
+            if c.is_whitespace() || c == ';' {
+                pos += 1;
+            } else if c.is_ascii_digit() {
+                while pos < chars.len() && chars[pos].is_ascii_digit() {
+                    pos += 1;
+                }
+                let digits = chars[start..pos].iter().collect::<String>();
+                match digits.parse() {
+                    Ok(n) => tokens.push((Token::Number(n), line)),
+                    Err(_) => return error(line, format!("number `{}` is too large", digits)),
+                }
+            } else if c.is_alphabetic() || c == '_' {
+                while pos < chars.len() && (chars[pos].is_alphanumeric() || chars[pos] == '_') {
+                    pos += 1;
+                }
+                tokens.push((Token::Ident(chars[start..pos].iter().collect()), line));
+            } else {
+                let rest = chars[pos..].iter().take(2).collect::<String>();
+                match SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) {
+                    Some(symbol) => {
+                        tokens.push((Token::Symbol(symbol), line));
+                        pos += symbol.len();
+                    }
+                    None => return error(line, format!("unexpected character `{}`", c)),
+                }
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Number(i64),
+    Variable(String, usize),
+    Binary(&'static str, Box<Expr>, Box<Expr>, usize),
+}
+
+#[derive(Debug, Clone)]
+struct Condition {
+    left: Expr,
+    comparison: &'static str,
+    right: Expr,
+}
+
+#[derive(Debug, Clone)]
+enum Stmt {
+    Command(fn(i64) -> Command, Expr, usize),
+    Let(String, Expr, usize),
+    Repeat(Expr, Vec<Stmt>, usize),
+    If(Condition, Vec<Stmt>, Vec<Stmt>, usize),
+    Call(String, usize),
+}
+
+impl Stmt {
+    fn line(&self) -> usize {
+        match self {
+            Stmt::Command(_, _, line)
+            | Stmt::Let(_, _, line)
+            | Stmt::Repeat(_, _, line)
+            | Stmt::If(_, _, _, line)
+            | Stmt::Call(_, line) => *line,
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    nesting: usize,
+    macros: HashMap<String, (Vec<Stmt>, usize)>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or_else(|| self.tokens.last())
+            .map_or(1, |(_, line)| *line)
+    }
+
+    fn next(&mut self) -> Result<Token, ScriptError> {
+        match self.tokens.get(self.pos) {
+            Some((token, _)) => {
+                self.pos += 1;
+                Ok(token.clone())
+            }
+            None => error(self.line(), "unexpected end of script"),
+        }
+    }
+
+    // Enters one more level of nesting; the caller restores `self.nesting` when done.
+    fn nest(&mut self, line: usize) -> Result<(), ScriptError> {
+        self.nesting += 1;
+        if self.nesting > MAX_NESTING {
+            return error(
+                line,
+                format!("the script nests more than {} deep", MAX_NESTING),
+            );
+        }
+        Ok(())
+    }
+
+    // Whether the next token, on the same line, starts an amount rather than a
+    // statement.
+    fn amount_follows(&self, line: usize) -> bool {
+        match self.tokens.get(self.pos) {
+            Some((Token::Number(_), next)) | Some((Token::Symbol("(" | "-"), next)) => {
+                *next == line
+            }
+            _ => false,
+        }
+    }
+
+    fn next_is(&self, symbol: &str) -> bool {
+        matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol)
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), ScriptError> {
+        let line = self.line();
+        match self.next()? {
+            Token::Symbol(s) if s == symbol => Ok(()),
+            token => error(line, format!("expected `{}`, found {}", symbol, token)),
+        }
+    }
+
+    fn name(&mut self) -> Result<String, ScriptError> {
+        let line = self.line();
+        match self.next()? {
+            Token::Ident(name) if RESERVED.contains(&name.as_str()) => {
+                error(line, format!("`{}` is a reserved word", name))
+            }
+            Token::Ident(name) => Ok(name),
+            token => error(line, format!("expected a name, found {}", token)),
+        }
+    }
+
+    fn factor(&mut self) -> Result<Expr, ScriptError> {
+        let (line, outer) = (self.line(), self.nesting);
+        let factor = match self.next()? {
+            Token::Number(n) => Expr::Number(n),
+            Token::Ident(name) => Expr::Variable(name, line),
+            Token::Symbol("-") => {
+                self.nest(line)?;
+                Expr::Binary(
+                    "-",
+                    Box::new(Expr::Number(0)),
+                    Box::new(self.factor()?),
+                    line,
+                )
+            }
+            Token::Symbol("(") => {
+                self.nest(line)?;
+                let expr = self.expr()?;
+                self.expect(")")?;
+                expr
+            }
+            token => {
+                return error(
+                    line,
+                    format!("expected a number or a name, found {}", token),
+                )
+            }
+        };
+        self.nesting = outer;
+        Ok(factor)
+    }
+
+    // Chained operators nest too: `1 + 2 + 3` is `(1 + 2) + 3`.
+    fn term(&mut self) -> Result<Expr, ScriptError> {
+        let outer = self.nesting;
+        let mut expr = self.factor()?;
+        while self.next_is("*") {
+            let line = self.line();
+            self.nest(line)?;
+            self.pos += 1;
+            expr = Expr::Binary("*", Box::new(expr), Box::new(self.factor()?), line);
+        }
+        self.nesting = outer;
+        Ok(expr)
+    }
+
+    fn expr(&mut self) -> Result<Expr, ScriptError> {
+        let outer = self.nesting;
+        let mut expr = self.term()?;
+        while self.next_is("+") || self.next_is("-") {
+            let line = self.line();
+            self.nest(line)?;
+            let op = if self.next_is("+") { "+" } else { "-" };
+            self.pos += 1;
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.term()?), line);
+        }
+        self.nesting = outer;
+        Ok(expr)
+    }
+
+    fn condition(&mut self) -> Result<Condition, ScriptError> {
+        let left = self.expr()?;
+        let line = self.line();
+        let comparison = match self.next()? {
+            Token::Symbol(s) if [">", "<", ">=", "<=", "==", "!="].contains(&s) => s,
+            token => return error(line, format!("expected a comparison, found {}", token)),
+        };
+        let right = self.expr()?;
+        Ok(Condition {
+            left,
+            comparison,
+            right,
+        })
+    }
+
+    fn block(&mut self) -> Result<Vec<Stmt>, ScriptError> {
+        let (opened, outer) = (self.line(), self.nesting);
+        self.expect("{")?;
+        self.nest(opened)?;
+        let mut statements = Vec::<Stmt>::new();
+        loop {
+            match self.peek() {
+                Some(Token::Symbol("}")) => {
+                    self.pos += 1;
+                    self.nesting = outer;
+                    return Ok(statements);
+                }
+                Some(_) => statements.push(self.statement(false)?),
+                None => return error(opened, "`{` is never closed"),
+            }
+        }
+    }
+
+    // Macro definitions are hoisted into `self.macros` and leave a no-op behind.
+    fn statement(&mut self, top_level: bool) -> Result<Stmt, ScriptError> {
+        let line = self.line();
+        let word = match self.next()? {
+            Token::Ident(word) => word,
+            token => return error(line, format!("expected a statement, found {}", token)),
+        };
+        match word.as_str() {
+            "forward" => Ok(Stmt::Command(Command::Forward, self.expr()?, line)),
+            "down" => Ok(Stmt::Command(Command::Down, self.expr()?, line)),
+            "up" => Ok(Stmt::Command(Command::Up, self.expr()?, line)),
+            "repeat" => {
+                let count = self.expr()?;
+                Ok(Stmt::Repeat(count, self.block()?, line))
+            }
+            "if" => {
+                let condition = self.condition()?;
+                let then = self.block()?;
+                let otherwise = if matches!(self.peek(), Some(Token::Ident(w)) if w == "else") {
+                    self.pos += 1;
+                    self.block()?
+                } else {
+                    Vec::new()
+                };
+                Ok(Stmt::If(condition, then, otherwise, line))
+            }
+            "let" => {
+                let name = self.name()?;
+                self.expect("=")?;
+                Ok(Stmt::Let(name, self.expr()?, line))
+            }
+            "macro" if !top_level => error(line, "macros can only be defined at the top level"),
+            "macro" => {
+                let name = self.name()?;
+                if let Some((_, defined)) = self.macros.get(&name) {
+                    return error(
+                        line,
+                        format!("macro `{}` is already defined on line {}", name, defined),
+                    );
+                }
+                let body = self.block()?;
+                self.macros.insert(name, (body, line));
+                Ok(Stmt::Repeat(Expr::Number(0), Vec::new(), line))
+            }
+            "else" => error(line, "`else` without a matching `if`"),
+            _ if RESERVED.contains(&word.as_str()) => {
+                error(line, format!("`{}` cannot be used as a statement", word))
+            }
+            // Macros take no arguments, and no statement starts with a number: this
+            // is a command the submarine doesn't know, like `sideways 3`.
+            _ if self.amount_follows(line) => error(line, format!("unknown command `{}`", word)),
+            _ => Ok(Stmt::Call(word, line)),
+        }
+    }
+}
+
+pub struct Program {
+    statements: Vec<Stmt>,
+    macros: HashMap<String, (Vec<Stmt>, usize)>,
+}
+
+pub fn parse(source: &str) -> Result<Program, ScriptError> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        pos: 0,
+        nesting: 0,
+        macros: HashMap::new(),
+    };
+    let mut statements = Vec::<Stmt>::new();
+    while parser.peek().is_some() {
+        statements.push(parser.statement(true)?);
+    }
+    Ok(Program {
+        statements,
+        macros: parser.macros,
+    })
+}
+
+struct Interpreter<'a, M> {
+    submarine: Submarine<M>,
+    variables: HashMap<String, i64>,
+    macros: &'a HashMap<String, (Vec<Stmt>, usize)>,
+    calls: usize,
+    steps: usize,
+}
+
+impl<'a, M: NavigationModel> Interpreter<'a, M> {
+    fn eval(&self, expr: &Expr) -> Result<i64, ScriptError> {
+        match expr {
+            Expr::Number(n) => Ok(*n),
+            Expr::Variable(name, line) => {
+                let position = self.submarine.position();
+                match name.as_str() {
+                    "horizontal" => Ok(position.horizontal),
+                    "depth" => Ok(position.depth),
+                    "aim" => Ok(position.aim),
+                    _ => match self.variables.get(name) {
+                        Some(value) => Ok(*value),
+                        None => error(*line, format!("variable `{}` is not defined", name)),
+                    },
+                }
+            }
+            Expr::Binary(op, left, right, line) => {
+                let (left, right) = (self.eval(left)?, self.eval(right)?);
+                let result = match *op {
+                    "+" => left.checked_add(right),
+                    "-" => left.checked_sub(right),
+                    _ => left.checked_mul(right),
+                };
+                result.map_or_else(|| error(*line, "arithmetic overflow"), Ok)
+            }
+        }
+    }
+
+    fn holds(&self, condition: &Condition) -> Result<bool, ScriptError> {
+        let left = self.eval(&condition.left)?;
+        let right = self.eval(&condition.right)?;
+        Ok(match condition.comparison {
+            ">" => left > right,
+            "<" => left < right,
+            ">=" => left >= right,
+            "<=" => left <= right,
+            "==" => left == right,
+            _ => left != right,
+        })
+    }
+
+    fn block(&mut self, statements: &[Stmt]) -> Result<(), ScriptError> {
+        statements
+            .iter()
+            .try_for_each(|statement| self.statement(statement))
+    }
+
+    // Counts one step against MAX_STEPS, so no script (not even `repeat` with an
+    // empty body) runs forever.
+    fn step(&mut self, line: usize) -> Result<(), ScriptError> {
+        self.steps += 1;
+        if self.steps > MAX_STEPS {
+            return error(
+                line,
+                format!("the script runs more than {} steps", MAX_STEPS),
+            );
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self, statement: &Stmt) -> Result<(), ScriptError> {
+        self.step(statement.line())?;
+        match statement {
+            Stmt::Command(command, amount, line) => {
+                let amount = self.eval(amount)?;
+                if self.submarine.try_execute(command(amount)).is_none() {
+                    return error(*line, "the position overflows");
+                }
+            }
+            Stmt::Let(name, value, _) => {
+                let value = self.eval(value)?;
+                self.variables.insert(name.clone(), value);
+            }
+            Stmt::Repeat(count, body, line) => {
+                let count = self.eval(count)?;
+                if count < 0 {
+                    return error(*line, format!("cannot repeat {} times", count));
+                }
+                for _ in 0..count {
+                    self.step(*line)?;
+                    self.block(body)?;
+                }
+            }
+            Stmt::If(condition, then, otherwise, _) => {
+                if self.holds(condition)? {
+                    self.block(then)?;
+                } else {
+                    self.block(otherwise)?;
+                }
+            }
+            Stmt::Call(name, line) => {
+                let macros = self.macros;
+                let (body, _) = match macros.get(name) {
+                    Some(found) => found,
+                    None => return error(*line, format!("macro `{}` is not defined", name)),
+                };
+                if self.calls >= MAX_CALL_DEPTH {
+                    return error(
+                        *line,
+                        format!("macros are nested more than {} deep", MAX_CALL_DEPTH),
+                    );
+                }
+                self.calls += 1;
+                self.block(body)?;
+                self.calls -= 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Program {
+    // Returns the final position and every position visited, like `d02::navigate`.
+    pub fn run<M: NavigationModel>(
+        &self,
+        model: M,
+    ) -> Result<(Position, Vec<Position>), ScriptError> {
+        let mut interpreter = Interpreter {
+            submarine: Submarine::new(model),
+            variables: HashMap::new(),
+            macros: &self.macros,
+            calls: 0,
+            steps: 0,
+        };
+        interpreter.block(&self.statements)?;
+        let submarine = interpreter.submarine;
+        Ok((submarine.position(), submarine.history().to_vec()))
+    }
+}
+
+pub fn run_script<M: NavigationModel>(
+    source: &str,
+    model: M,
+) -> Result<(Position, Vec<Position>), ScriptError> {
+    parse(source)?.run(model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{navigate, parse_commands, Aimed, Plain};
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn example_course_as_a_script() {
+        let example = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
+        let lines = example.lines().map(String::from).collect::<Vec<String>>();
+        let course = parse_commands(&lines).unwrap();
+        assert_eq!(run_script(example, Plain), Ok(navigate(Plain, &course)));
+        assert_eq!(run_script(example, Aimed), Ok(navigate(Aimed, &course)));
+
+        let looped = "let n = 5\n\
+                      macro leg { forward n down n }\n\
+                      repeat 2 { leg }\n\
+                      if depth > 20 { up 3 } else { up 1000 }\n";
+        let (position, _) = run_script(looped, Aimed).unwrap();
+        assert_eq!(
+            (position.horizontal, position.depth, position.aim),
+            (10, 25, 7)
+        );
+    }
+
+    fn error_in(source: &str) -> String {
+        run_script(source, Aimed).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn lexing_and_parsing_errors_name_their_line() {
+        assert_eq!(
+            error_in("forward 1\nforward 2 % 3"),
+            "line 2: unexpected character `%`"
+        );
+        assert_eq!(
+            error_in("down 99999999999999999999"),
+            "line 1: number `99999999999999999999` is too large"
+        );
+        assert_eq!(
+            error_in("forward 5\nsideways 3"),
+            "line 2: unknown command `sideways`"
+        );
+        assert_eq!(
+            error_in("down 1\nup (2"),
+            "line 2: unexpected end of script"
+        );
+        assert_eq!(
+            error_in("repeat 2 {\n  up 1\n"),
+            "line 1: `{` is never closed"
+        );
+        assert_eq!(
+            error_in("up 1\n} down 2"),
+            "line 2: expected a statement, found `}`"
+        );
+        assert_eq!(
+            error_in("else { up 1 }"),
+            "line 1: `else` without a matching `if`"
+        );
+        assert_eq!(
+            error_in("let depth = 3"),
+            "line 1: `depth` is a reserved word"
+        );
+        assert_eq!(
+            error_in("aim"),
+            "line 1: `aim` cannot be used as a statement"
+        );
+        assert_eq!(
+            error_in("if depth { up 1 }"),
+            "line 1: expected a comparison, found `{`"
+        );
+        assert_eq!(
+            error_in("repeat 2 {\n  macro m { up 1 }\n}"),
+            "line 2: macros can only be defined at the top level"
+        );
+        assert_eq!(
+            error_in("macro m { up 1 }\n\nmacro m { up 2 }"),
+            "line 3: macro `m` is already defined on line 1"
+        );
+    }
+
+    #[test]
+    fn runtime_errors_name_their_line() {
+        assert_eq!(
+            error_in("let a = 1\nforward b"),
+            "line 2: variable `b` is not defined"
+        );
+        assert_eq!(
+            error_in("up 1\nrepeat 2 { dive }"),
+            "line 2: macro `dive` is not defined"
+        );
+        assert_eq!(
+            error_in("let n = 0 - 2\nrepeat n { up 1 }"),
+            "line 2: cannot repeat -2 times"
+        );
+        assert_eq!(
+            error_in("let big = 9223372036854775807\n\nforward big + 1"),
+            "line 3: arithmetic overflow"
+        );
+        assert_eq!(
+            error_in("macro a { b }\nmacro b { a }\na"),
+            "line 2: macros are nested more than 64 deep"
+        );
+        assert_eq!(
+            error_in("\nrepeat 100000 { repeat 100000 { } }"),
+            "line 2: the script runs more than 10000000 steps"
+        );
+    }
+
+    #[test]
+    fn deep_nesting_is_an_error() {
+        let parentheses = format!("forward {}1{}", "(".repeat(3000), ")".repeat(3000));
+        assert_eq!(
+            error_in(&parentheses),
+            "line 1: the script nests more than 256 deep"
+        );
+        let negations = format!("up {}1", "-".repeat(3000));
+        assert_eq!(
+            error_in(&negations),
+            "line 1: the script nests more than 256 deep"
+        );
+        let sum = format!("down 1{}", " + 1".repeat(3000));
+        assert_eq!(
+            error_in(&sum),
+            "line 1: the script nests more than 256 deep"
+        );
+        let blocks = format!(
+            "up 1\n{}{}",
+            "repeat 1 {\n".repeat(3000),
+            "}\n".repeat(3000)
+        );
+        assert_eq!(
+            error_in(&blocks),
+            "line 258: the script nests more than 256 deep"
+        );
+
+        let nested = format!("forward {}1{}", "(".repeat(200), ")".repeat(200));
+        assert!(run_script(&nested, Aimed).is_ok());
+    }
+
+    // A course of commands, rendered as a plain command list.
+    fn course() -> impl Strategy<Value = Vec<Command>> {
+        let command = prop_oneof![
+            (0_i64..10).prop_map(Command::Forward),
+            (0_i64..10).prop_map(Command::Down),
+            (0_i64..10).prop_map(Command::Up),
+        ];
+        prop::collection::vec(command, 0..30)
+    }
+
+    fn course_script(course: &[Command]) -> String {
+        course
+            .iter()
+            .map(|command| match command {
+                Command::Forward(x) => format!("forward {}\n", x),
+                Command::Down(x) => format!("down {}\n", x),
+                Command::Up(x) => format!("up {}\n", x),
+            })
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn scripts_follow_the_aim_semantics(course in course(), times in 0_i64..4) {
+            let script = course_script(&course);
+            let expected = navigate(Aimed, &course);
+            prop_assert_eq!(run_script(&script, Aimed), Ok(expected));
+
+            let repeated = course.repeat(times as usize);
+            let looped = format!("macro leg {{\n{}}}\nlet n = {}\nrepeat n {{ leg }}\n", script, times);
+            let expected = navigate(Aimed, &repeated);
+            prop_assert_eq!(run_script(&looped, Aimed), Ok(expected));
+        }
+
+        #[test]
+        fn deep_dives_overflow_with_an_error(
+            down in prop_oneof![0_i64..100_000, 0_i64..i64::MAX / 2],
+            forward in prop_oneof![0_i64..100_000, 0_i64..i64::MAX / 2],
+            times in 1_i64..4,
+        ) {
+            let script = format!("repeat {} {{ down {} forward {} }}", times, down, forward);
+            // The same dive in i128, which can't overflow here.
+            let (mut horizontal, mut depth, mut aim) = (0_i128, 0_i128, 0_i128);
+            let mut fits = true;
+            for _ in 0..times {
+                aim += down as i128;
+                horizontal += forward as i128;
+                depth += forward as i128 * aim;
+                fits &= [horizontal, depth, aim].iter().all(|v| i64::try_from(*v).is_ok());
+            }
+            match run_script(&script, Aimed) {
+                Ok((position, _)) => {
+                    prop_assert!(fits);
+                    prop_assert_eq!(position.depth as i128, depth);
+                }
+                Err(error) => {
+                    prop_assert!(!fits);
+                    prop_assert_eq!(error.line, 1);
+                }
+            }
+        }
+    }
+}
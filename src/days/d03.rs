@@ -1,4 +1,7 @@
 use crate::utils::read_file_lines;
+use crate::utils::report::answer;
+use std::fmt;
+use std::ops::{BitAnd, BitOr, Not, Shl};
 
 /*
 The submarine has been making some odd creaking noises, so you ask it to produce a diagnostic report just in case.
@@ -35,32 +38,434 @@ The epsilon rate is calculated in a similar way; rather than use the most common
 Use the binary numbers in your diagnostic report to calculate the gamma rate and epsilon rate, then multiply them together. What is the power consumption of the submarine? (Be sure to represent your answer in decimal, not binary.)
 */
 
-pub fn part1() {
-    let binaries: Vec<String> = read_file_lines("input/03.txt");
-    let binary_len = binaries[0].len();
-    let binaries_len = binaries.len();
-    let mut ones_count = vec![0; binary_len];
-
-    binaries.into_iter().for_each(|binary| {
-        binary.chars().enumerate().for_each(|(i, c)| {
-            ones_count[i] += if c == '0' { 0 } else { 1 };
-        });
-    });
-
-    let mut gamma_rate = 0;
-    let mut epsilon_rate = 0;
-    for (i, count) in ones_count.iter().enumerate() {
-        let most_common = if binaries_len - count > binaries_len / 2 {
-            0
-        } else {
-            1
+// The integer types a report can be decoded into.
+pub trait Word:
+    Copy
+    + Ord
+    + fmt::Debug
+    + Not<Output = Self>
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + Shl<u32, Output = Self>
+{
+    const BITS: u32;
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn from_binary(digits: &str) -> Option<Self>;
+}
+
+macro_rules! impl_word {
+    ($($t:ty),*) => {$(
+        impl Word for $t {
+            const BITS: u32 = <$t>::BITS;
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn from_binary(digits: &str) -> Option<Self> {
+                <$t>::from_str_radix(digits, 2).ok()
+            }
+        }
+    )*};
+}
+
+impl_word!(u32, u64, u128);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReportError {
+    InvalidDigit {
+        line: usize,
+        text: String,
+    },
+    WidthMismatch {
+        line: usize,
+        expected: u32,
+        found: u32,
+    },
+    TooWide {
+        width: u32,
+        bits: u32,
+    },
+}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReportError::InvalidDigit { line, text } => {
+                write!(f, "line {}: `{}` is not a binary number", line, text)
+            }
+            ReportError::WidthMismatch {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: expected {} bits like the first line, found {}",
+                line, expected, found
+            ),
+            ReportError::TooWide { width, bits } => {
+                write!(f, "{}-bit numbers don't fit in a {}-bit word", width, bits)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReportError {}
+
+// A diagnostic report: one word per line, `width` bits each. Bit positions are
+// counted from the left, as in the puzzle statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report<W> {
+    pub width: u32,
+    pub words: Vec<W>,
+}
+
+pub fn parse_report<W: Word>(lines: &[String]) -> Result<Report<W>, ReportError> {
+    let mut width = None;
+    let mut words = Vec::<W>::with_capacity(lines.len());
+    for (i, text) in lines.iter().enumerate() {
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let invalid = || ReportError::InvalidDigit {
+            line: i + 1,
+            text: text.to_string(),
+        };
+        if !text.bytes().all(|b| b == b'0' || b == b'1') {
+            return Err(invalid());
+        }
+        let found = text.len() as u32;
+        let expected = *width.get_or_insert(found);
+        if found != expected {
+            return Err(ReportError::WidthMismatch {
+                line: i + 1,
+                expected,
+                found,
+            });
+        }
+        if found > W::BITS {
+            return Err(ReportError::TooWide {
+                width: found,
+                bits: W::BITS,
+            });
+        }
+        words.push(W::from_binary(text).ok_or_else(invalid)?);
+    }
+    Ok(Report {
+        width: width.unwrap_or(0),
+        words,
+    })
+}
+
+impl<W: Word> Report<W> {
+    // All `width` bits set.
+    pub fn mask(&self) -> W {
+        (0..self.width).fold(W::ZERO, |mask, i| mask | W::ONE << i)
+    }
+
+    // The mask selecting the bit at `pos`, counted from the left.
+    pub fn bit(&self, pos: u32) -> W {
+        W::ONE << (self.width - 1 - pos)
+    }
+
+    pub fn ones_at(&self, pos: u32) -> usize {
+        let bit = self.bit(pos);
+        self.words.iter().filter(|w| **w & bit != W::ZERO).count()
+    }
+
+    // Most common bit at every position, 1 on ties.
+    pub fn gamma(&self) -> W {
+        (0..self.width)
+            .filter(|pos| 2 * self.ones_at(*pos) >= self.words.len())
+            .fold(W::ZERO, |gamma, pos| gamma | self.bit(pos))
+    }
+
+    pub fn epsilon(&self) -> W {
+        !self.gamma() & self.mask()
+    }
+
+    pub fn oxygen_generator(&self) -> Option<W> {
+        self.select(&MostCommon(true)).rating
+    }
+
+    pub fn co2_scrubber(&self) -> Option<W> {
+        self.select(&LeastCommon(false)).rating
+    }
+
+    // Sorted words sharing a prefix form a contiguous range, and the ones with the
+    // next bit set come last: each step of the bit criteria just narrows the range.
+    pub fn select<C: BitCriteria>(&self, criteria: &C) -> Selection<W> {
+        let mut sorted = self.words.clone();
+        sorted.sort_unstable();
+        let mut log = Vec::<Elimination<W>>::new();
+        let (mut lo, mut hi) = (0, sorted.len());
+        for pos in 0..self.width {
+            if hi - lo <= 1 {
+                break;
+            }
+            let bit = self.bit(pos);
+            let split = lo + sorted[lo..hi].partition_point(|w| *w & bit == W::ZERO);
+            let (zeros, ones) = (split - lo, hi - split);
+            // When every word agrees on the bit there is nothing to discard.
+            let kept = if zeros == 0 || ones == 0 {
+                ones > 0
+            } else {
+                criteria.keep(pos, zeros, ones)
+            };
+            let eliminated = if kept {
+                let eliminated = sorted[lo..split].to_vec();
+                lo = split;
+                eliminated
+            } else {
+                let eliminated = sorted[split..hi].to_vec();
+                hi = split;
+                eliminated
+            };
+            log.push(Elimination {
+                pos,
+                zeros,
+                ones,
+                kept,
+                eliminated,
+            });
+        }
+        Selection {
+            width: self.width,
+            rating: sorted.get(lo).copied().filter(|_| lo < hi),
+            log,
+        }
+    }
+}
+
+// Binary trie over the words of a report, most significant bit first. Every node
+// counts the words below it, so a rating is a single walk from the root. Paths are
+// only spelled out bit by bit where words share them: a node reached by a single
+// word keeps that word instead of a chain of one-child nodes down to the last bit.
+pub struct Trie<W> {
+    width: u32,
+    nodes: Vec<Node<W>>,
+}
+
+// Children are node indices, with 0 (the root) meaning "none". A node other than the
+// root with a count of 1 is a leaf holding its only word.
+#[derive(Clone, Copy)]
+struct Node<W> {
+    count: u32,
+    children: [u32; 2],
+    word: W,
+}
+
+impl<W: Word> Trie<W> {
+    pub fn new(report: &Report<W>) -> Self {
+        let mut trie = Trie {
+            width: report.width,
+            nodes: vec![Trie::leaf(W::ZERO)],
         };
-        let least_common = if most_common == 0 { 1 } else { 0 };
-        gamma_rate += most_common << (binary_len - i - 1);
-        epsilon_rate += least_common << (binary_len - i - 1);
+        trie.nodes[0].count = 0;
+        report.words.iter().for_each(|word| trie.insert(*word));
+        trie
+    }
+
+    fn leaf(word: W) -> Node<W> {
+        Node {
+            count: 1,
+            children: [0, 0],
+            word,
+        }
+    }
+
+    fn side(word: W, pos: u32) -> usize {
+        (word & W::ONE << pos != W::ZERO) as usize
+    }
+
+    fn is_leaf(&self, node: usize) -> bool {
+        node != 0 && self.nodes[node].count == 1
+    }
+
+    pub fn insert(&mut self, word: W) {
+        let mut node = 0;
+        self.nodes[0].count += 1;
+        for pos in (0..self.width).rev() {
+            let side = Trie::side(word, pos);
+            let child = self.nodes[node].children[side] as usize;
+            if child == 0 {
+                self.nodes.push(Trie::leaf(word));
+                self.nodes[node].children[side] = (self.nodes.len() - 1) as u32;
+                return;
+            }
+            // A second word reaches the leaf: its word moves one bit further down.
+            if self.is_leaf(child) && pos > 0 {
+                let other = self.nodes[child].word;
+                self.nodes.push(Trie::leaf(other));
+                self.nodes[child].children[Trie::side(other, pos - 1)] =
+                    (self.nodes.len() - 1) as u32;
+            }
+            self.nodes[child].count += 1;
+            node = child;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes[0].count as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // How many words start with the `len` bits of `prefix` (its least significant ones).
+    pub fn prefix_count(&self, prefix: W, len: u32) -> usize {
+        assert!(
+            len <= self.width,
+            "A {}-bit prefix is longer than the {}-bit words",
+            len,
+            self.width
+        );
+        let mut node = 0;
+        // Bit `k` of the prefix, counting from its most significant one.
+        let bit = |word: W, k: u32| Trie::side(word, len - 1 - k);
+        for k in 0..len {
+            if self.is_leaf(node) {
+                let word = self.nodes[node].word;
+                let matches =
+                    (k..len).all(|k| bit(prefix, k) == Trie::side(word, self.width - 1 - k));
+                return matches as usize;
+            }
+            node = self.nodes[node].children[bit(prefix, k)] as usize;
+            if node == 0 {
+                return 0;
+            }
+        }
+        self.nodes[node].count as usize
+    }
+
+    // Same decisions as `Report::select`, without the elimination log.
+    pub fn rating<C: BitCriteria>(&self, criteria: &C) -> Option<W> {
+        if self.is_empty() {
+            return None;
+        }
+        let (mut node, mut rating) = (0, W::ZERO);
+        for pos in 0..self.width {
+            if self.is_leaf(node) {
+                return Some(self.nodes[node].word);
+            }
+            let [zero, one] = self.nodes[node].children;
+            let count = |child: u32| match child {
+                0 => 0,
+                _ => self.nodes[child as usize].count as usize,
+            };
+            let (zeros, ones) = (count(zero), count(one));
+            let kept = if zeros == 0 || ones == 0 {
+                ones > 0
+            } else {
+                criteria.keep(pos, zeros, ones)
+            };
+            rating = rating << 1;
+            if kept {
+                rating = rating | W::ONE;
+                node = one as usize;
+            } else {
+                node = zero as usize;
+            }
+        }
+        Some(rating)
+    }
+}
+
+// Decides which bit value (`true` for 1) survives at `pos`, given how many of the
+// remaining words have a 0 and a 1 there. Only asked when both values occur.
+pub trait BitCriteria {
+    fn keep(&self, pos: u32, zeros: usize, ones: usize) -> bool;
+}
+
+// Oxygen generator rule; the field is the bit kept on ties.
+pub struct MostCommon(pub bool);
+
+impl BitCriteria for MostCommon {
+    fn keep(&self, _pos: u32, zeros: usize, ones: usize) -> bool {
+        if zeros == ones {
+            self.0
+        } else {
+            ones > zeros
+        }
+    }
+}
+
+// CO2 scrubber rule; the field is the bit kept on ties.
+pub struct LeastCommon(pub bool);
+
+impl BitCriteria for LeastCommon {
+    fn keep(&self, _pos: u32, zeros: usize, ones: usize) -> bool {
+        if zeros == ones {
+            self.0
+        } else {
+            ones < zeros
+        }
+    }
+}
+
+impl<F: Fn(u32, usize, usize) -> bool> BitCriteria for F {
+    fn keep(&self, pos: u32, zeros: usize, ones: usize) -> bool {
+        self(pos, zeros, ones)
+    }
+}
+
+// What the criteria decided at one bit position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elimination<W> {
+    pub pos: u32,
+    pub zeros: usize,
+    pub ones: usize,
+    pub kept: bool,
+    pub eliminated: Vec<W>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection<W> {
+    pub width: u32,
+    pub rating: Option<W>,
+    pub log: Vec<Elimination<W>>,
+}
+
+impl<W: Word + fmt::Binary> fmt::Display for Selection<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let width = self.width as usize;
+        for step in &self.log {
+            let eliminated = step
+                .eliminated
+                .iter()
+                .map(|word| format!("{:0width$b}", word, width = width))
+                .collect::<Vec<String>>();
+            writeln!(
+                f,
+                "bit {}: {} zeros, {} ones, keep {}, eliminated {}",
+                step.pos,
+                step.zeros,
+                step.ones,
+                step.kept as u8,
+                if eliminated.is_empty() {
+                    "none".to_string()
+                } else {
+                    eliminated.join(" ")
+                }
+            )?;
+        }
+        match self.rating {
+            Some(rating) => write!(f, "rating: {:0width$b}", rating, width = width),
+            None => write!(f, "rating: none"),
+        }
     }
+}
+
+fn diagnostic_report() -> Report<u64> {
+    parse_report(&read_file_lines("input/03.txt")).unwrap_or_else(|error| panic!("{}", error))
+}
+
+pub fn part1() {
+    let report = diagnostic_report();
 
-    println!("Day 03 > Part 1: {}", gamma_rate * epsilon_rate);
+    answer(3, 1, report.gamma() * report.epsilon());
 }
 
 /*
@@ -98,57 +503,166 @@ Finally, to find the life support rating, multiply the oxygen generator rating (
 Use the binary numbers in your diagnostic report to calculate the oxygen generator rating and CO2 scrubber rating, then multiply them together. What is the life support rating of the submarine? (Be sure to represent your answer in decimal, not binary.)
 */
 
-fn sweep<'a>(binaries: &[&'a String], pos: usize, use_ones: bool) -> Vec<&'a String> {
-    let binaries_len = binaries.len();
-    let mut ones_count = 0;
+pub fn part2() {
+    let trie = Trie::new(&diagnostic_report());
+    let oxygen_generator: u64 = trie.rating(&MostCommon(true)).expect("The report is empty");
+    let co2_scrubber: u64 = trie
+        .rating(&LeastCommon(false))
+        .expect("The report is empty");
 
-    binaries.iter().for_each(|binary| {
-        let c: char = binary.chars().collect::<Vec<char>>()[pos];
-        ones_count += if c == '0' { 0 } else { 1 };
-    });
+    answer(3, 2, oxygen_generator * co2_scrubber);
+}
 
-    let most_common = if binaries_len - ones_count > binaries_len / 2 {
-        if use_ones {
-            '0'
-        } else {
-            '1'
-        }
-    } else if use_ones {
-        '1'
-    } else {
-        '0'
-    };
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
 
-    binaries
-        .iter()
-        .filter(|binary| binary.chars().collect::<Vec<char>>()[pos] == most_common)
-        .copied()
-        .collect()
-}
+    const EXAMPLE: [&str; 12] = [
+        "00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000", "11001",
+        "00010", "01010",
+    ];
 
-fn reduce(binaries: &[&String], uses_ones: bool) -> i32 {
-    let binary_len = binaries[0].len();
-    let mut index = 0;
-    let mut swept: Vec<&String> = binaries.to_vec();
-    loop {
-        swept = sweep(&swept, index, uses_ones);
-        if swept.len() == 1 {
-            break;
-        }
-        index += 1;
+    fn example() -> Report<u32> {
+        parse_report(&EXAMPLE.map(String::from)).unwrap()
     }
 
-    swept[0].chars().enumerate().fold(0, |acc, (i, c)| {
-        acc + (c.to_string().parse::<i32>().unwrap() << (binary_len - i - 1))
-    })
-}
+    #[test]
+    fn example_rates() {
+        let report = example();
+        assert_eq!((report.gamma(), report.epsilon()), (22, 9));
+        assert_eq!(report.oxygen_generator(), Some(23));
+        assert_eq!(report.co2_scrubber(), Some(10));
+    }
 
-pub fn part2() {
-    let data: Vec<String> = read_file_lines("input/03.txt");
-    let binaries: Vec<&String> = data.iter().collect();
+    #[test]
+    fn example_oxygen_generator_log() {
+        let selection = example().select(&MostCommon(true));
+        assert_eq!(
+            selection.to_string(),
+            "bit 0: 5 zeros, 7 ones, keep 1, eliminated 00010 00100 00111 01010 01111\n\
+             bit 1: 4 zeros, 3 ones, keep 0, eliminated 11001 11100 11110\n\
+             bit 2: 1 zeros, 3 ones, keep 1, eliminated 10000\n\
+             bit 3: 1 zeros, 2 ones, keep 1, eliminated 10101\n\
+             bit 4: 1 zeros, 1 ones, keep 1, eliminated 10110\n\
+             rating: 10111"
+        );
+    }
+
+    #[test]
+    fn example_trie() {
+        let mut trie = Trie::new(&example());
+        assert_eq!(trie.len(), 12);
+        assert_eq!(trie.rating(&MostCommon(true)), Some(23));
+        assert_eq!(trie.rating(&LeastCommon(false)), Some(10));
+        let prefixes = [
+            (0, 0),
+            (0b1, 1),
+            (0b10, 2),
+            (0b101, 3),
+            (0b10110, 5),
+            (0b10011, 5),
+        ];
+        let counts = prefixes.map(|(prefix, len)| trie.prefix_count(prefix, len));
+        assert_eq!(counts, [12, 7, 4, 3, 1, 0]);
+        // Copies of a word share its leaf, all the way down to the last bit.
+        trie.insert(0b10110);
+        trie.insert(0b10110);
+        assert_eq!(trie.prefix_count(0b10110, 5), 3);
+        assert_eq!(trie.prefix_count(0b1011, 4), 4);
+        assert_eq!(trie.rating(&MostCommon(true)), Some(0b10110));
+    }
+
+    #[test]
+    #[should_panic(expected = "A 6-bit prefix is longer than the 5-bit words")]
+    fn prefixes_longer_than_the_words_are_rejected() {
+        Trie::new(&example()).prefix_count(0, 6);
+    }
 
-    let oxygen_rate = reduce(&binaries, true);
-    let c02_scrubber = reduce(&binaries, false);
+    #[test]
+    fn malformed_reports_name_their_line() {
+        let lines = ["0101", "0111", "011"].map(String::from);
+        assert_eq!(
+            parse_report::<u32>(&lines).unwrap_err().to_string(),
+            "line 3: expected 4 bits like the first line, found 3"
+        );
+        let lines = ["0101", "01a1"].map(String::from);
+        assert_eq!(
+            parse_report::<u32>(&lines).unwrap_err().to_string(),
+            "line 2: `01a1` is not a binary number"
+        );
+        let lines = ["1".repeat(40)];
+        assert_eq!(
+            parse_report::<u32>(&lines).unwrap_err().to_string(),
+            "40-bit numbers don't fit in a 32-bit word"
+        );
+    }
 
-    println!("Day 03 > Part 2: {}", c02_scrubber * oxygen_rate);
+    // A diagnostic report of equally wide binary numbers, up to 128 bits.
+    fn diagnostic_report() -> impl Strategy<Value = Vec<String>> {
+        (1_usize..=128).prop_flat_map(|width| {
+            prop::collection::vec(prop::collection::vec(any::<bool>(), width), 1..40).prop_map(
+                |words| {
+                    words
+                        .iter()
+                        .map(|bits| bits.iter().map(|b| if *b { '1' } else { '0' }).collect())
+                        .collect()
+                },
+            )
+        })
+    }
+
+    // The bit criteria exactly as the statement describes them, on strings.
+    fn rate_naively(report: &[String], most_common: bool) -> String {
+        let mut remaining = report.to_vec();
+        let mut pos = 0;
+        while remaining.len() > 1 && pos < remaining[0].len() {
+            let ones = remaining
+                .iter()
+                .filter(|word| word.as_bytes()[pos] == b'1')
+                .count();
+            let zeros = remaining.len() - ones;
+            if ones > 0 && zeros > 0 {
+                let keep = if (ones >= zeros) == most_common {
+                    b'1'
+                } else {
+                    b'0'
+                };
+                remaining.retain(|word| word.as_bytes()[pos] == keep);
+            }
+            pos += 1;
+        }
+        remaining[0].clone()
+    }
+
+    proptest! {
+        #[test]
+        fn bit_mask_ratings_match_the_bit_criteria(lines in diagnostic_report()) {
+            let report = parse_report::<u128>(&lines).unwrap();
+            let width = report.width as usize;
+            let binary = |word: u128| format!("{:0width$b}", word, width = width);
+            prop_assert_eq!(binary(report.oxygen_generator().unwrap()), rate_naively(&lines, true));
+            prop_assert_eq!(binary(report.co2_scrubber().unwrap()), rate_naively(&lines, false));
+            prop_assert_eq!(report.gamma() & report.epsilon(), 0);
+            prop_assert_eq!(report.gamma() | report.epsilon(), report.mask());
+            let selection = report.select(&|_pos: u32, zeros: usize, ones: usize| ones >= zeros);
+            prop_assert_eq!(selection.rating, report.oxygen_generator());
+            let eliminated = selection.log.iter().map(|step| step.eliminated.len()).sum::<usize>();
+            // Identical words are never split up, so every copy of the rating survives.
+            let copies = report.words.iter().filter(|w| Some(**w) == selection.rating).count();
+            prop_assert_eq!(eliminated + copies, lines.len());
+            let trie = Trie::new(&report);
+            prop_assert_eq!(trie.rating(&LeastCommon(true)), report.select(&LeastCommon(true)).rating);
+            for len in 0..=report.width {
+                let top = |word: u128| word.checked_shr(report.width - len).unwrap_or(0);
+                let prefix = top(report.words[0]);
+                let starting_with = report.words.iter().filter(|w| top(**w) == prefix).count();
+                prop_assert_eq!(trie.prefix_count(prefix, len), starting_with);
+            }
+            if width <= 64 {
+                let narrow = parse_report::<u64>(&lines).unwrap();
+                prop_assert_eq!(narrow.gamma() as u128, report.gamma());
+            }
+        }
+    }
 }
@@ -1,6 +1,10 @@
-use crate::utils::read_file_lines;
+use crate::utils::cache::{load, Cached};
+use crate::utils::config::param;
+use crate::utils::report::answer;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
 use std::collections::HashMap;
-use std::collections::HashSet;
 
 /*
 You're already almost 1.5km (almost a mile) below the surface of the ocean, already so deep that you can't see any sunlight. What you can see, however, is a giant squid that has attached itself to the outside of your submarine.
@@ -62,12 +66,516 @@ The score of the winning board can now be calculated. Start by finding the sum o
 To guarantee victory against the giant squid, figure out which board will win first. What will your final score be if you choose that board?
 */
 
-const SIZE: usize = 5;
+// The puzzle's rules only count rows and columns ("Diagonals don't count").
+fn diagonals() -> bool {
+    param("d04.diagonals", false)
+}
+
+// A set of cells, one bit each in 128-cell words.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct CellSet(Vec<u128>);
+
+// The cells of a winning line, as the bits it needs in every word it touches.
+type LineMask = Vec<(usize, u128)>;
+
+impl CellSet {
+    fn with_capacity(cells: usize) -> Self {
+        CellSet(vec![0; cells.div_ceil(128)])
+    }
+
+    fn insert(&mut self, cell: usize) {
+        self.0[cell / 128] |= 1 << (cell % 128);
+    }
+
+    fn contains(&self, cell: usize) -> bool {
+        self.0[cell / 128] >> (cell % 128) & 1 == 1
+    }
+
+    fn covers(&self, line: &LineMask) -> bool {
+        line.iter()
+            .all(|(word, bits)| self.0[*word] & bits == *bits)
+    }
+}
+
+fn line_mask(cells: &[usize]) -> LineMask {
+    let mut mask = LineMask::new();
+    for cell in cells {
+        let (word, bit) = (cell / 128, 1 << (cell % 128));
+        match mask.iter_mut().find(|(w, _)| *w == word) {
+            Some((_, bits)) => *bits |= bit,
+            None => mask.push((word, bit)),
+        }
+    }
+    mask
+}
+
+// Cells are numbered row by row, and `marked` holds the ones marked so far.
+#[derive(Clone)]
+pub struct Board {
+    rows: usize,
+    cols: usize,
+    cells: Vec<i32>,
+    marked: CellSet,
+}
+
+impl Board {
+    pub fn new(rows: Vec<Vec<i32>>) -> Self {
+        let cols = rows.first().map_or(0, |row| row.len());
+        assert!(
+            rows.iter().all(|row| row.len() == cols),
+            "Every row of a board must have the same length"
+        );
+        Board {
+            rows: rows.len(),
+            cols,
+            marked: CellSet::with_capacity(rows.len() * cols),
+            cells: rows.concat(),
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn cell(&self, row: usize, col: usize) -> i32 {
+        self.cells[row * self.cols + col]
+    }
+
+    pub fn is_marked(&self, row: usize, col: usize) -> bool {
+        self.marked.contains(row * self.cols + col)
+    }
+
+    pub fn marked(&self) -> impl Iterator<Item = i32> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.marked.contains(*i))
+            .map(|(_, n)| *n)
+    }
+
+    pub fn unmarked(&self) -> impl Iterator<Item = i32> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !self.marked.contains(*i))
+            .map(|(_, n)| *n)
+    }
+}
+
+// A set of winning lines (groups of cells that must all be marked) for a board shape.
+pub trait WinPattern {
+    fn lines(&self, rows: usize, cols: usize) -> Vec<Vec<usize>>;
+}
+
+pub struct Rows;
+
+impl WinPattern for Rows {
+    fn lines(&self, rows: usize, cols: usize) -> Vec<Vec<usize>> {
+        (0..rows)
+            .map(|r| (0..cols).map(|c| r * cols + c).collect())
+            .collect()
+    }
+}
+
+pub struct Columns;
 
-struct Board {
-    hits: Vec<i32>,
-    unmarked: HashSet<i32>,
-    numbers: HashMap<i32, (usize, usize)>,
+impl WinPattern for Columns {
+    fn lines(&self, rows: usize, cols: usize) -> Vec<Vec<usize>> {
+        (0..cols)
+            .map(|c| (0..rows).map(|r| r * cols + c).collect())
+            .collect()
+    }
+}
+
+// Only square boards have diagonals.
+pub struct Diagonals;
+
+impl WinPattern for Diagonals {
+    fn lines(&self, rows: usize, cols: usize) -> Vec<Vec<usize>> {
+        if rows != cols || rows == 0 {
+            return Vec::new();
+        }
+        vec![
+            (0..rows).map(|i| i * cols + i).collect(),
+            (0..rows).map(|i| i * cols + cols - 1 - i).collect(),
+        ]
+    }
+}
+
+pub struct FourCorners;
+
+impl WinPattern for FourCorners {
+    fn lines(&self, rows: usize, cols: usize) -> Vec<Vec<usize>> {
+        if rows == 0 || cols == 0 {
+            return Vec::new();
+        }
+        // Single-row or single-column boards have fewer than four corners.
+        let mut corners = vec![0, cols - 1, (rows - 1) * cols, rows * cols - 1];
+        corners.sort_unstable();
+        corners.dedup();
+        vec![corners]
+    }
+}
+
+pub struct FullCard;
+
+impl WinPattern for FullCard {
+    fn lines(&self, rows: usize, cols: usize) -> Vec<Vec<usize>> {
+        vec![(0..rows * cols).collect()]
+    }
+}
+
+// A single custom line, drawn with `X` for the cells it needs, e.g. ["X...X", ...].
+// Cells outside the board make the mask unwinnable on it.
+pub struct Mask(pub Vec<(usize, usize)>);
+
+impl Mask {
+    pub fn from_rows(rows: &[&str]) -> Self {
+        Mask(
+            rows.iter()
+                .enumerate()
+                .flat_map(|(r, row)| {
+                    row.chars()
+                        .enumerate()
+                        .filter(|(_, c)| *c == 'X')
+                        .map(move |(c, _)| (r, c))
+                })
+                .collect(),
+        )
+    }
+}
+
+impl WinPattern for Mask {
+    fn lines(&self, rows: usize, cols: usize) -> Vec<Vec<usize>> {
+        if self.0.iter().any(|(r, c)| *r >= rows || *c >= cols) {
+            return Vec::new();
+        }
+        vec![self.0.iter().map(|(r, c)| r * cols + c).collect()]
+    }
+}
+
+impl<F: Fn(usize, usize) -> Vec<Vec<usize>>> WinPattern for F {
+    fn lines(&self, rows: usize, cols: usize) -> Vec<Vec<usize>> {
+        self(rows, cols)
+    }
+}
+
+// The score of a board that just won with `number`.
+pub trait Scoring {
+    fn score(&self, board: &Board, number: i32) -> i64;
+}
+
+// The puzzle's score: sum of the unmarked numbers times the winning number.
+pub struct UnmarkedSum;
+
+impl Scoring for UnmarkedSum {
+    fn score(&self, board: &Board, number: i32) -> i64 {
+        board.unmarked().map(i64::from).sum::<i64>() * number as i64
+    }
+}
+
+impl<F: Fn(&Board, i32) -> i64> Scoring for F {
+    fn score(&self, board: &Board, number: i32) -> i64 {
+        self(board, number)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Win {
+    pub board: usize,
+    pub draw: usize,
+    pub number: i32,
+    pub score: i64,
+}
+
+// What happened when a number was drawn: the boards it was marked on and the ones it
+// completed (in board order).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Draw {
+    pub number: i32,
+    pub marked: Vec<usize>,
+    pub completed: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timeline {
+    pub draws: Vec<Draw>,
+    // In winning order.
+    pub wins: Vec<Win>,
+    // Index into `wins` for every board.
+    by_board: Vec<Option<usize>>,
+}
+
+impl Timeline {
+    pub fn first(&self) -> Option<&Win> {
+        self.wins.first()
+    }
+
+    pub fn last(&self) -> Option<&Win> {
+        self.wins.last()
+    }
+
+    // The k-th board to win, counting from 0.
+    pub fn nth(&self, k: usize) -> Option<&Win> {
+        self.wins.get(k)
+    }
+
+    pub fn win_of(&self, board: usize) -> Option<&Win> {
+        self.win_order(board).map(|k| &self.wins[k])
+    }
+
+    // Position of the board in the winning order, counting from 0.
+    pub fn win_order(&self, board: usize) -> Option<usize> {
+        self.by_board.get(board).copied().flatten()
+    }
+
+    pub fn never_wins(&self) -> Vec<usize> {
+        (0..self.by_board.len())
+            .filter(|board| self.by_board[*board].is_none())
+            .collect()
+    }
+}
+
+// What playing a game needs besides its draws, which reshuffling them leaves alone:
+// where every number is, and the line masks of every board (by shape).
+struct Layout {
+    index: HashMap<i32, Vec<(usize, usize)>>,
+    shape: Vec<usize>,
+    lines: Vec<Vec<Vec<LineMask>>>,
+}
+
+pub struct Bingo {
+    patterns: Vec<Box<dyn WinPattern>>,
+    scoring: Box<dyn Scoring>,
+}
+
+impl Default for Bingo {
+    fn default() -> Self {
+        Bingo::new()
+    }
+}
+
+impl Bingo {
+    // No pattern and the puzzle's scoring: add patterns with `with`.
+    pub fn new() -> Self {
+        Bingo {
+            patterns: Vec::new(),
+            scoring: Box::new(UnmarkedSum),
+        }
+    }
+
+    // The puzzle's rules: rows and columns, scored by `UnmarkedSum`.
+    pub fn puzzle() -> Self {
+        Bingo::new().with(Rows).with(Columns)
+    }
+
+    pub fn with<P: WinPattern + 'static>(mut self, pattern: P) -> Self {
+        self.patterns.push(Box::new(pattern));
+        self
+    }
+
+    pub fn scored_by<S: Scoring + 'static>(mut self, scoring: S) -> Self {
+        self.scoring = Box::new(scoring);
+        self
+    }
+
+    // For every cell, the masks of the winning lines going through it.
+    fn lines_by_cell(&self, rows: usize, cols: usize) -> Vec<Vec<LineMask>> {
+        let mut by_cell = vec![Vec::new(); rows * cols];
+        for line in self.patterns.iter().flat_map(|p| p.lines(rows, cols)) {
+            let mask = line_mask(&line);
+            line.iter()
+                .for_each(|cell| by_cell[*cell].push(mask.clone()));
+        }
+        by_cell
+    }
+
+    fn layout(&self, game: &Game) -> Layout {
+        // Boards of the same shape share their line masks.
+        let mut shapes = HashMap::<(usize, usize), usize>::new();
+        let mut lines = Vec::<Vec<Vec<LineMask>>>::new();
+        let shape = game
+            .boards
+            .iter()
+            .map(|board| {
+                *shapes.entry((board.rows, board.cols)).or_insert_with(|| {
+                    lines.push(self.lines_by_cell(board.rows, board.cols));
+                    lines.len() - 1
+                })
+            })
+            .collect::<Vec<usize>>();
+        Layout {
+            index: game.index(),
+            shape,
+            lines,
+        }
+    }
+
+    // Plays every draw, recording the whole game. A board stops playing (and
+    // marking numbers) once it has won.
+    pub fn play(&self, game: &Game) -> Timeline {
+        self.play_draws(game, &self.layout(game), &game.draws)
+    }
+
+    fn play_draws(&self, game: &Game, layout: &Layout, draws: &[i32]) -> Timeline {
+        let Layout {
+            index,
+            shape,
+            lines,
+        } = layout;
+        let mut boards = game.boards.clone();
+        let mut timeline = Timeline {
+            draws: Vec::with_capacity(draws.len()),
+            wins: Vec::new(),
+            by_board: vec![None; boards.len()],
+        };
+        for (draw, number) in draws.iter().enumerate() {
+            let mut marked = Vec::<usize>::new();
+            let mut completed = Vec::<usize>::new();
+            let cells = index.get(number).map_or(&[][..], |cells| &cells[..]);
+            let mut start = 0;
+            // The same number can be on a board more than once: mark all its cells
+            // before checking for lines.
+            while start < cells.len() {
+                let i = cells[start].0;
+                let end = start + cells[start..].iter().take_while(|(b, _)| *b == i).count();
+                let hits = &cells[start..end];
+                start = end;
+                if timeline.by_board[i].is_some() {
+                    continue;
+                }
+                let board = &mut boards[i];
+                hits.iter().for_each(|(_, cell)| board.marked.insert(*cell));
+                marked.push(i);
+                let won = hits.iter().any(|(_, cell)| {
+                    lines[shape[i]][*cell]
+                        .iter()
+                        .any(|line| board.marked.covers(line))
+                });
+                if won {
+                    completed.push(i);
+                    timeline.by_board[i] = Some(timeline.wins.len());
+                    timeline.wins.push(Win {
+                        board: i,
+                        draw,
+                        number: *number,
+                        score: self.scoring.score(board, *number),
+                    });
+                }
+            }
+            timeline.draws.push(Draw {
+                number: *number,
+                marked,
+                completed,
+            });
+        }
+        timeline
+    }
+}
+
+// A proportion or a mean, with its 95% confidence interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub value: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+const Z_95: f64 = 1.96;
+
+// Wilson score interval: unlike the normal approximation it stays within [0, 1] and
+// behaves for boards that (almost) never win.
+fn proportion(successes: usize, trials: usize) -> Estimate {
+    if trials == 0 {
+        return Estimate {
+            value: 0.0,
+            low: 0.0,
+            high: 1.0,
+        };
+    }
+    let (n, p) = (trials as f64, successes as f64 / trials as f64);
+    let z2 = Z_95 * Z_95;
+    let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+    let margin = Z_95 / (1.0 + z2 / n) * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+    Estimate {
+        value: p,
+        low: (center - margin).max(0.0),
+        high: (center + margin).min(1.0),
+    }
+}
+
+// None below two samples, which say nothing about the spread.
+fn mean(samples: &[f64]) -> Option<Estimate> {
+    if samples.len() < 2 {
+        return None;
+    }
+    let n = samples.len() as f64;
+    let value = samples.iter().sum::<f64>() / n;
+    let variance = samples.iter().map(|x| (x - value).powi(2)).sum::<f64>() / (n - 1.0);
+    let margin = Z_95 * (variance / n).sqrt();
+    Some(Estimate {
+        value,
+        low: value - margin,
+        high: value + margin,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardOdds {
+    pub board: usize,
+    pub first: Estimate,
+    pub last: Estimate,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonteCarlo {
+    pub trials: usize,
+    pub seed: u64,
+    // Trials in which no board won at all.
+    pub no_win: usize,
+    // Number of draws until the first win, over the trials with a winner (if there
+    // were at least two).
+    pub draws_to_first_win: Option<Estimate>,
+    pub boards: Vec<BoardOdds>,
+}
+
+impl Bingo {
+    // Plays the game with `trials` random orders of its draws. Boards completing on
+    // the same draw are ordered like in `play`, i.e. by board index.
+    pub fn monte_carlo(&self, game: &Game, trials: usize, seed: u64) -> MonteCarlo {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let layout = self.layout(game);
+        let mut shuffled = game.draws.clone();
+        let mut first = vec![0; game.boards.len()];
+        let mut last = vec![0; game.boards.len()];
+        let mut draws_to_first_win = Vec::<f64>::with_capacity(trials);
+        for _ in 0..trials {
+            shuffled.shuffle(&mut rng);
+            let timeline = self.play_draws(game, &layout, &shuffled);
+            if let (Some(winner), Some(loser)) = (timeline.first(), timeline.last()) {
+                first[winner.board] += 1;
+                last[loser.board] += 1;
+                draws_to_first_win.push((winner.draw + 1) as f64);
+            }
+        }
+        MonteCarlo {
+            trials,
+            seed,
+            no_win: trials - draws_to_first_win.len(),
+            draws_to_first_win: mean(&draws_to_first_win),
+            boards: (0..game.boards.len())
+                .map(|board| BoardOdds {
+                    board,
+                    first: proportion(first[board], trials),
+                    last: proportion(last[board], trials),
+                })
+                .collect(),
+        }
+    }
 }
 
 fn line_to_numbers(line: &str) -> Vec<i32> {
@@ -80,81 +588,114 @@ fn line_to_numbers(line: &str) -> Vec<i32> {
     }
 }
 
-fn check_for_lines(board: &Board, number: &i32) -> bool {
-    let (i, j) = board.numbers[number];
-    let mut same_row_found = 0;
-    let mut same_column_found = 0;
-    for number in &board.hits {
-        let (hit_i, hit_j) = board.numbers[number];
-        if hit_i == i {
-            same_row_found += 1;
-        }
-        if hit_j == j {
-            same_column_found += 1;
+#[derive(Clone)]
+pub struct Game {
+    pub draws: Vec<i32>,
+    pub boards: Vec<Board>,
+}
+
+impl Game {
+    // Where every number is: (board, cell) pairs in board then cell order.
+    pub fn index(&self) -> HashMap<i32, Vec<(usize, usize)>> {
+        let mut index = HashMap::<i32, Vec<(usize, usize)>>::new();
+        for (i, board) in self.boards.iter().enumerate() {
+            for (cell, number) in board.cells.iter().enumerate() {
+                index.entry(*number).or_default().push((i, cell));
+            }
         }
+        index
     }
-    same_row_found == SIZE || same_column_found == SIZE
 }
 
-fn calculate_result(board: &Board) -> i32 {
-    board.unmarked.iter().sum()
+// The draws on the first line, then one board per line: its number of rows and
+// columns, followed by its cells row by row.
+impl Cached for Game {
+    const FORMAT: u32 = 2;
+
+    fn encode(&self) -> String {
+        let numbers = |numbers: &[i32]| {
+            numbers
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<String>>()
+                .join(" ")
+        };
+        let mut lines = vec![numbers(&self.draws)];
+        for board in &self.boards {
+            lines.push(format!(
+                "{} {} {}",
+                board.rows,
+                board.cols,
+                numbers(&board.cells)
+            ));
+        }
+        lines.join("\n")
+    }
+
+    fn decode(data: &str) -> Option<Self> {
+        let mut lines = data.split('\n');
+        let draws = lines
+            .next()?
+            .split_whitespace()
+            .map(|x| x.parse().ok())
+            .collect::<Option<Vec<i32>>>()?;
+        let boards = lines
+            .map(|line| {
+                let mut fields = line.split_whitespace();
+                let rows = fields.next()?.parse::<usize>().ok()?;
+                let cols = fields.next()?.parse::<usize>().ok()?;
+                let cells = fields
+                    .map(|x| x.parse().ok())
+                    .collect::<Option<Vec<i32>>>()?;
+                (cells.len() == rows.checked_mul(cols)?).then(|| Board {
+                    rows,
+                    cols,
+                    marked: CellSet::with_capacity(cells.len()),
+                    cells,
+                })
+            })
+            .collect::<Option<Vec<Board>>>()?;
+        Some(Game { draws, boards })
+    }
 }
 
-fn parse_bingo() -> (Vec<i32>, Vec<Board>) {
-    let mut bingo = read_file_lines("input/04.txt").into_iter();
-    let random_numbers: Vec<i32> = bingo
+// Boards are separated by blank lines and may have any (rectangular) shape.
+pub fn parse_game(lines: &[String]) -> Game {
+    let mut game = lines.iter();
+    let draws: Vec<i32> = game
         .next()
         .unwrap()
         .split(',')
         .map(|x| x.parse().unwrap())
         .collect();
     let mut boards: Vec<Board> = Vec::new();
-
-    let mut i: usize = 0;
-    for line in bingo {
-        let numbers = line_to_numbers(&line);
+    let mut rows: Vec<Vec<i32>> = Vec::new();
+    for line in game.chain(std::iter::once(&String::new())) {
+        let numbers = line_to_numbers(line.trim());
         if numbers.is_empty() {
-            boards.push(Board {
-                hits: Vec::new(),
-                unmarked: HashSet::new(),
-                numbers: HashMap::new(),
-            });
-            i = 0;
+            if !rows.is_empty() {
+                boards.push(Board::new(std::mem::take(&mut rows)));
+            }
         } else {
-            numbers.into_iter().enumerate().for_each(|(j, number)| {
-                boards.last_mut().unwrap().numbers.insert(number, (i, j));
-                boards.last_mut().unwrap().unmarked.insert(number);
-            });
-            i += 1;
+            rows.push(numbers);
         }
     }
-    (random_numbers, boards)
+    Game { draws, boards }
 }
 
-pub fn part1() {
-    let (random_numbers, mut boards) = parse_bingo();
-
-    let mut result: i32 = -1;
-    let mut done = false;
-    for number in &random_numbers {
-        if done {
-            break;
-        }
+fn bingo() -> Bingo {
+    if diagonals() {
+        Bingo::puzzle().with(Diagonals)
+    } else {
+        Bingo::puzzle()
+    }
+}
 
-        for board in &mut boards {
-            if board.numbers.contains_key(number) {
-                board.hits.push(*number);
-                board.unmarked.remove(number);
+pub fn part1() {
+    let game = load("input/04.txt", parse_game);
+    let timeline = bingo().play(&game);
 
-                if board.hits.len() >= 5 && check_for_lines(board, number) {
-                    result = calculate_result(board) * number;
-                    done = true;
-                    break;
-                }
-            }
-        }
-    }
-    println!("Day 04 > Part 1: {}", result);
+    answer(4, 1, timeline.first().map_or(-1, |win| win.score));
 }
 
 /*
@@ -166,29 +707,245 @@ In the above example, the second board is the last to win, which happens after 1
 
 Figure out which board will win last. Once it wins, what would its final score be?
 */
+
 pub fn part2() {
-    let (random_numbers, mut boards) = parse_bingo();
-
-    let mut winning_boards: HashSet<usize> = HashSet::new();
-    let mut last_won: usize = usize::MAX;
-    let mut last_won_number: i32 = -1;
-    for number in &random_numbers {
-        for (idx, board) in boards.iter_mut().enumerate() {
-            if !winning_boards.contains(&idx) && board.numbers.contains_key(number) {
-                board.hits.push(*number);
-                board.unmarked.remove(number);
-
-                if board.hits.len() >= 5 && check_for_lines(board, number) {
-                    last_won = idx;
-                    last_won_number = *number;
-                    winning_boards.insert(idx);
+    let game = load("input/04.txt", parse_game);
+    let timeline = bingo().play(&game);
+
+    answer(4, 2, timeline.last().map_or(-1, |win| win.score));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
+
+22 13 17 11  0
+ 8  2 23  4 24
+21  9 14 16  7
+ 6 10  3 18  5
+ 1 12 20 15 19
+
+ 3 15  0  2 22
+ 9 18 13 17  5
+19  8  7 25 23
+20 11 10 24  4
+14 21 16 12  6
+
+14 21 17 24  4
+10 16 15  9 19
+18  8 23 26 20
+22 11 13  6  5
+ 2  0 12  3  7";
+
+    fn example() -> Game {
+        parse_game(&EXAMPLE.lines().map(String::from).collect::<Vec<String>>())
+    }
+
+    #[test]
+    fn example_first_and_last_winners() {
+        let timeline = Bingo::puzzle().play(&example());
+        let first = Win {
+            board: 2,
+            draw: 11,
+            number: 24,
+            score: 4512,
+        };
+        let last = Win {
+            board: 1,
+            draw: 14,
+            number: 13,
+            score: 1924,
+        };
+        assert_eq!(timeline.first(), Some(&first));
+        assert_eq!(timeline.last(), Some(&last));
+        assert_eq!(timeline.win_order(0), Some(1));
+        assert!(timeline.never_wins().is_empty());
+        // After the first five draws, nobody has won yet.
+        assert!(timeline.draws[..5]
+            .iter()
+            .all(|draw| draw.completed.is_empty()));
+        assert_eq!(timeline.draws[0].marked, [0, 1, 2]);
+    }
+
+    #[test]
+    fn games_round_trip_through_the_cache() {
+        let game = example();
+        let decoded = Game::decode(&game.encode()).unwrap();
+        assert_eq!(decoded.draws, game.draws);
+        let cells = |game: &Game| {
+            game.boards
+                .iter()
+                .map(|board| (board.rows, board.cols, board.cells.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(cells(&decoded), cells(&game));
+        assert!(Game::decode("7 4 9\n2 2 1 2 3").is_none());
+        assert!(Game::decode("7 4 9\n2 x 1 2 3 4").is_none());
+    }
+
+    #[test]
+    fn pattern_lines() {
+        assert_eq!(Diagonals.lines(3, 3), [vec![0, 4, 8], vec![2, 4, 6]]);
+        assert!(Diagonals.lines(2, 3).is_empty());
+        assert!(Diagonals.lines(0, 0).is_empty());
+        assert_eq!(FourCorners.lines(2, 3), [vec![0, 2, 3, 5]]);
+        assert_eq!(FourCorners.lines(1, 3), [vec![0, 2]]);
+        assert_eq!(FourCorners.lines(1, 1), [vec![0]]);
+        assert_eq!(FullCard.lines(2, 2), [vec![0, 1, 2, 3]]);
+        let mask = Mask::from_rows(&["X..", ".X.", "..X"]);
+        assert_eq!(mask.0, [(0, 0), (1, 1), (2, 2)]);
+        assert_eq!(mask.lines(3, 4), [vec![0, 5, 10]]);
+        assert!(mask.lines(2, 3).is_empty());
+        let first_column = |rows: usize, cols: usize| vec![(0..rows).map(|r| r * cols).collect()];
+        assert_eq!(first_column.lines(3, 2), [vec![0, 2, 4]]);
+    }
+
+    // A 2x3 board and a 3x3 board with the same first two rows.
+    fn two_shapes(draws: &[i32]) -> Game {
+        let rows = [vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        Game {
+            draws: draws.to_vec(),
+            boards: vec![Board::new(rows[..2].to_vec()), Board::new(rows.to_vec())],
+        }
+    }
+
+    fn wins(timeline: &Timeline) -> Vec<(usize, usize, i64)> {
+        timeline
+            .wins
+            .iter()
+            .map(|win| (win.board, win.draw, win.score))
+            .collect()
+    }
+
+    #[test]
+    fn patterns_on_boards_of_different_shapes() {
+        // Only the square board has diagonals.
+        let timeline = Bingo::new()
+            .with(Diagonals)
+            .play(&two_shapes(&[1, 5, 9, 3, 7]));
+        assert_eq!(wins(&timeline), [(1, 2, 30 * 9)]);
+        assert_eq!(timeline.never_wins(), [0]);
+
+        let timeline = Bingo::new()
+            .with(FourCorners)
+            .play(&two_shapes(&[1, 3, 4, 6, 7, 9]));
+        assert_eq!(wins(&timeline), [(0, 3, 7 * 6), (1, 5, 15 * 9)]);
+
+        let timeline = Bingo::new()
+            .with(Mask::from_rows(&["X.X", ".X."]))
+            .play(&two_shapes(&[5, 1, 3]));
+        assert_eq!(wins(&timeline), [(0, 2, 12 * 3), (1, 2, 36 * 3)]);
+        assert_eq!(timeline.draws[2].completed, [0, 1]);
+
+        let first_column = |rows: usize, cols: usize| vec![(0..rows).map(|r| r * cols).collect()];
+        let timeline = Bingo::new().with(first_column).play(&two_shapes(&[4, 1]));
+        assert_eq!(wins(&timeline), [(0, 1, 16)]);
+    }
+
+    #[test]
+    fn custom_scoring() {
+        let marked_then_number =
+            |board: &Board, number: i32| board.marked().count() as i64 * 100 + number as i64;
+        let timeline = Bingo::new()
+            .with(FullCard)
+            .scored_by(marked_then_number)
+            .play(&two_shapes(&[1, 2, 3, 4, 5, 6]));
+        assert_eq!(wins(&timeline), [(0, 5, 606)]);
+        assert_eq!(timeline.never_wins(), [1]);
+    }
+
+    // Boards of any shape (numbers may repeat) and a long sequence of draws. Large
+    // boards span several words of marks.
+    fn bingo_game() -> impl Strategy<Value = (Vec<Vec<Vec<i32>>>, Vec<i32>)> {
+        prop_oneof![
+            (1_usize..6, 1_usize..6, Just(30_i32)),
+            (9_usize..16, 9_usize..16, Just(300_i32)),
+        ]
+        .prop_flat_map(|(rows, cols, numbers)| {
+            let board = prop::collection::vec(prop::collection::vec(0..numbers, cols), rows);
+            (
+                prop::collection::vec(board, 1..8),
+                prop::collection::vec(0..numbers, 0..2 * numbers as usize),
+            )
+        })
+    }
+
+    // Rows and columns only, checked by rescanning the whole board after every mark.
+    fn play_naively(boards: &[Vec<Vec<i32>>], draws: &[i32]) -> Vec<(usize, usize, i64)> {
+        let mut marked = boards
+            .iter()
+            .map(|board| vec![vec![false; board[0].len()]; board.len()])
+            .collect::<Vec<_>>();
+        let mut won = vec![false; boards.len()];
+        let mut wins = Vec::new();
+        for (draw, number) in draws.iter().enumerate() {
+            for (i, board) in boards.iter().enumerate() {
+                if won[i] || !board.iter().flatten().any(|n| n == number) {
+                    continue;
+                }
+                for (r, row) in board.iter().enumerate() {
+                    for (c, n) in row.iter().enumerate() {
+                        marked[i][r][c] |= n == number;
+                    }
+                }
+                let full_row = marked[i].iter().any(|row| row.iter().all(|m| *m));
+                let full_col = (0..board[0].len()).any(|c| marked[i].iter().all(|row| row[c]));
+                if full_row || full_col {
+                    won[i] = true;
+                    let unmarked = board
+                        .iter()
+                        .flatten()
+                        .zip(marked[i].iter().flatten())
+                        .filter(|(_, m)| !**m)
+                        .map(|(n, _)| *n as i64)
+                        .sum::<i64>();
+                    wins.push((i, draw, unmarked * *number as i64));
                 }
             }
         }
+        wins
     }
 
-    println!(
-        "Day 04 > Part 2: {}",
-        calculate_result(&boards[last_won]) * last_won_number
-    );
+    proptest! {
+        #[test]
+        fn bitmask_bingo_matches_naive_play((boards, draws) in bingo_game()) {
+            let game = Game {
+                draws: draws.clone(),
+                boards: boards.iter().cloned().map(Board::new).collect(),
+            };
+            let timeline = Bingo::puzzle().play(&game);
+            let wins = timeline
+                .wins
+                .iter()
+                .map(|win| (win.board, win.draw, win.score))
+                .collect::<Vec<_>>();
+            prop_assert_eq!(wins, play_naively(&boards, &draws));
+            for (k, win) in timeline.wins.iter().enumerate() {
+                prop_assert_eq!(timeline.win_order(win.board), Some(k));
+                prop_assert!(timeline.draws[win.draw].completed.contains(&win.board));
+            }
+            prop_assert_eq!(timeline.never_wins().len() + timeline.wins.len(), boards.len());
+        }
+
+        #[test]
+        fn monte_carlo_odds_are_reproducible_and_consistent(
+            (boards, draws) in bingo_game(),
+            seed in any::<u64>(),
+        ) {
+            let game = Game {
+                draws,
+                boards: boards.into_iter().map(Board::new).collect(),
+            };
+            let bingo = Bingo::puzzle();
+            let odds = bingo.monte_carlo(&game, 20, seed);
+            prop_assert_eq!(&odds, &bingo.monte_carlo(&game, 20, seed));
+            // Every trial has exactly one first winner, or none at all.
+            let first = odds.boards.iter().map(|board| board.first.value).sum::<f64>();
+            prop_assert!((first - (1.0 - odds.no_win as f64 / 20.0)).abs() < 1e-9);
+            prop_assert_eq!(odds.draws_to_first_win.is_some(), odds.no_win <= 18);
+        }
+    }
 }
@@ -1,5 +1,8 @@
+use crate::utils::config::param;
 use crate::utils::read_file_lines;
+use crate::utils::report::answer;
 use std::collections::HashMap;
+use std::str::FromStr;
 
 /*
 You come across a field of hydrothermal vents on the ocean floor! These vents constantly produce large, opaque clouds, so it would be best to avoid them if possible.
@@ -43,55 +46,588 @@ To avoid the most dangerous areas, you need to determine the number of points wh
 
 Consider only horizontal and vertical lines. At how many points do at least two lines overlap?
 */
-fn add_point(points: &mut HashMap<(i16, i16), i16>, (x, y): (i16, i16)) {
-    *points.entry((x, y)).or_insert(0) += 1;
+
+// A point with N integer coordinates. The puzzle's vents are planar (N = 2), but the
+// same lines can be drawn through 3D (or higher) vent fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Point<const N: usize = 2>(pub [i32; N]);
+
+impl Point<2> {
+    pub fn new(x: i32, y: i32) -> Self {
+        Point([x, y])
+    }
+
+    pub fn x(&self) -> i32 {
+        self.0[0]
+    }
+
+    pub fn y(&self) -> i32 {
+        self.0[1]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<const N: usize = 2> {
+    pub from: Point<N>,
+    pub to: Point<N>,
+}
+
+// How a line is turned into grid points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Raster {
+    // Only the points exactly on the segment (all of them for the puzzle's lines).
+    Exact,
+    // One point per step along the major axis, the closest to the segment.
+    Bresenham,
+}
+
+// Which lines are considered at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    // Axis-aligned lines.
+    Orthogonal,
+    // Orthogonal lines and 45° diagonals, i.e. lines moving by the same amount along
+    // every axis they move along.
+    Diagonal,
+    Any,
+}
+
+impl FromStr for Raster {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "exact" => Ok(Raster::Exact),
+            "bresenham" => Ok(Raster::Bresenham),
+            _ => Err(format!("unknown raster `{}`", s)),
+        }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "orthogonal" => Ok(Filter::Orthogonal),
+            "diagonal" => Ok(Filter::Diagonal),
+            "any" => Ok(Filter::Any),
+            _ => Err(format!("unknown filter `{}`", s)),
+        }
+    }
 }
 
-fn parse_coords() -> std::vec::IntoIter<(i16, i16, i16, i16)> {
-    read_file_lines("input/05.txt")
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl<const N: usize> Line<N> {
+    fn delta(&self) -> [i64; N] {
+        let mut delta = [0; N];
+        for (axis, d) in delta.iter_mut().enumerate() {
+            *d = self.to.0[axis] as i64 - self.from.0[axis] as i64;
+        }
+        delta
+    }
+
+    // The number of steps between consecutive lattice points, from `from` to `to`.
+    fn steps(&self) -> i64 {
+        self.delta().iter().fold(0, |steps, d| gcd(steps, *d))
+    }
+
+    fn at(&self, step: [i64; N], i: i64) -> Point<N> {
+        let mut point = self.from;
+        for (axis, c) in point.0.iter_mut().enumerate() {
+            *c = (*c as i64 + i * step[axis]) as i32;
+        }
+        point
+    }
+
+    pub fn is_orthogonal(&self) -> bool {
+        self.delta().iter().filter(|d| **d != 0).count() <= 1
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        let moves = self.delta().map(i64::abs);
+        let len = moves.iter().max().copied().unwrap_or(0);
+        moves.iter().all(|d| *d == 0 || *d == len)
+    }
+
+    pub fn passes(&self, filter: Filter) -> bool {
+        match filter {
+            Filter::Orthogonal => self.is_orthogonal(),
+            Filter::Diagonal => self.is_orthogonal() || self.is_diagonal(),
+            Filter::Any => true,
+        }
+    }
+
+    // Walks from `from` to `to` in steps of delta / gcd(delta).
+    pub fn lattice_points(&self) -> Vec<Point<N>> {
+        let steps = self.steps();
+        if steps == 0 {
+            return vec![self.from];
+        }
+        let step = self.delta().map(|d| d / steps);
+        (0..=steps).map(|i| self.at(step, i)).collect()
+    }
+
+    // At step i of the major axis, every coordinate is rounded to the nearest
+    // integer (halves towards +infinity), which is what Bresenham's algorithm
+    // draws in any number of dimensions.
+    pub fn bresenham(&self) -> Vec<Point<N>> {
+        let delta = self.delta();
+        let len = delta.iter().map(|d| d.abs()).max().unwrap_or(0);
+        if len == 0 {
+            return vec![self.from];
+        }
+        (0..=len)
+            .map(|i| {
+                let mut point = self.from;
+                for (axis, c) in point.0.iter_mut().enumerate() {
+                    *c = (*c as i64 + div_floor(2 * i * delta[axis] + len, 2 * len)) as i32;
+                }
+                point
+            })
+            .collect()
+    }
+
+    pub fn points(&self, raster: Raster) -> Vec<Point<N>> {
+        match raster {
+            Raster::Exact => self.lattice_points(),
+            Raster::Bresenham => self.bresenham(),
+        }
+    }
+
+    // The points inside the box [min, max]. Exact lines are clipped without walking
+    // the whole line; Bresenham lines are drawn and filtered.
+    pub fn points_within(&self, raster: Raster, min: Point<N>, max: Point<N>) -> Vec<Point<N>> {
+        let inside =
+            |p: &Point<N>| (0..N).all(|axis| min.0[axis] <= p.0[axis] && p.0[axis] <= max.0[axis]);
+        if raster == Raster::Bresenham {
+            return self.bresenham().into_iter().filter(inside).collect();
+        }
+        // A single-point line has no steps (and no direction) at all.
+        let steps = self.steps();
+        let step = self.delta().map(|d| d / steps.max(1));
+        // Steps i with lo <= start + i * s <= hi, along every axis.
+        let (mut first, mut last) = (0, steps);
+        for (axis, s) in step.iter().enumerate() {
+            let start = self.from.0[axis] as i64;
+            let (lo, hi) = (min.0[axis] as i64 - start, max.0[axis] as i64 - start);
+            let (i0, i1) = match *s {
+                0 if lo <= 0 && 0 <= hi => (0, steps),
+                0 => (1, 0),
+                s if s > 0 => (div_ceil(lo, s), div_floor(hi, s)),
+                s => (div_ceil(hi, s), div_floor(lo, s)),
+            };
+            first = first.max(i0);
+            last = last.min(i1);
+        }
+        (first..=last).map(|i| self.at(step, i)).collect()
+    }
+
+    pub fn covers(&self, raster: Raster, p: Point<N>) -> bool {
+        !self.points_within(raster, p, p).is_empty()
+    }
+}
+
+fn div_floor(a: i64, b: i64) -> i64 {
+    let q = a / b;
+    if a % b != 0 && (a < 0) != (b < 0) {
+        q - 1
+    } else {
+        q
+    }
+}
+
+fn div_ceil(a: i64, b: i64) -> i64 {
+    -div_floor(-a, b)
+}
+
+fn parse_point<const N: usize>(text: &str) -> Point<N> {
+    let coords = text
+        .trim()
+        .split(',')
+        .map(|x| x.trim().parse().unwrap())
+        .collect::<Vec<i32>>();
+    match coords.try_into() {
+        Ok(coords) => Point(coords),
+        Err(_) => panic!("Expected {} coordinates in `{}`", N, text.trim()),
+    }
+}
+
+// Lines like `x,y -> x,y` for N = 2, `x,y,z -> x,y,z` for N = 3, and so on.
+pub fn parse_lines<const N: usize>(lines: &[String]) -> Vec<Line<N>> {
+    lines
         .iter()
+        .filter(|line| !line.trim().is_empty())
         .map(|line| {
-            let mut parts = line.split(" -> ");
-            let coords_1: Vec<i16> = parts
-                .next()
-                .unwrap()
-                .split(',')
-                .map(|x| x.parse().unwrap())
-                .collect();
-            let coords_2: Vec<i16> = parts
-                .next()
-                .unwrap()
-                .split(',')
-                .map(|x| x.parse().unwrap())
-                .collect();
-            (coords_1[0], coords_1[1], coords_2[0], coords_2[1])
+            let mut ends = line.split("->");
+            Line {
+                from: parse_point(ends.next().unwrap()),
+                to: parse_point(ends.next().unwrap()),
+            }
         })
-        .collect::<Vec<(i16, i16, i16, i16)>>()
-        .into_iter()
+        .collect()
 }
 
-pub fn part1() {
-    let mut points: HashMap<(i16, i16), i16> = HashMap::new();
-    parse_coords()
-        .filter(|(x1, y1, x2, y2)| x1 == x2 || y1 == y2)
-        .for_each(|(x1, y1, x2, y2)| {
-            if x1 == x2 {
-                let range = if y1 < y2 { y1..(y2 + 1) } else { y2..(y1 + 1) };
-                for i in range {
-                    add_point(&mut points, (x1, i));
-                }
+// Above this many cells in the bounding box, lines are no longer drawn on a grid.
+const DENSE_CELLS: i128 = 1 << 22;
+
+// Number of points covered by at least `k` of the lines passing the filter. Small
+// fields are counted on a dense grid; large planar ones with `counted_sparsely`,
+// and the rest (Bresenham lines, which aren't evenly spaced, or more dimensions)
+// in a hash map.
+pub fn count_covered<const N: usize>(
+    lines: &[Line<N>],
+    k: u32,
+    filter: Filter,
+    raster: Raster,
+) -> u64 {
+    assert!(
+        k >= 1,
+        "Every point of the plane is covered by at least 0 lines"
+    );
+    let lines = lines
+        .iter()
+        .filter(|line| line.passes(filter))
+        .copied()
+        .collect::<Vec<Line<N>>>();
+    match bounds(&lines) {
+        Some((min, max)) if volume(min, max) <= DENSE_CELLS => {
+            counted_densely(&lines, k, raster, min, max)
+        }
+        Some(_) if raster == Raster::Exact && N == 2 => {
+            let planar = |p: Point<N>| Point::new(p.0[0], p.0[1]);
+            let lines = lines
+                .iter()
+                .map(|line| Line {
+                    from: planar(line.from),
+                    to: planar(line.to),
+                })
+                .collect::<Vec<Line>>();
+            counted_sparsely(&lines, k)
+        }
+        Some(_) => counted_in_map(&lines, k, raster),
+        None => 0,
+    }
+}
+
+fn volume<const N: usize>(min: Point<N>, max: Point<N>) -> i128 {
+    (0..N)
+        .map(|axis| max.0[axis] as i128 - min.0[axis] as i128 + 1)
+        .product()
+}
+
+fn bounds<const N: usize>(lines: &[Line<N>]) -> Option<(Point<N>, Point<N>)> {
+    let ends = lines.iter().flat_map(|line| [line.from, line.to]);
+    ends.fold(None, |bounds, p| match bounds {
+        None => Some((p, p)),
+        Some((mut min, mut max)) => {
+            for axis in 0..N {
+                min.0[axis] = min.0[axis].min(p.0[axis]);
+                max.0[axis] = max.0[axis].max(p.0[axis]);
+            }
+            Some((min, max))
+        }
+    })
+}
+
+pub fn counted_densely<const N: usize>(
+    lines: &[Line<N>],
+    k: u32,
+    raster: Raster,
+    min: Point<N>,
+    max: Point<N>,
+) -> u64 {
+    let mut grid = vec![0_u32; volume(min, max) as usize];
+    for point in lines.iter().flat_map(|line| line.points(raster)) {
+        // Row-major, the last axis varying fastest.
+        let cell = (0..N).fold(0, |cell, axis| {
+            cell * (max.0[axis] - min.0[axis] + 1) as usize + (point.0[axis] - min.0[axis]) as usize
+        });
+        grid[cell] += 1;
+    }
+    grid.iter().filter(|count| **count >= k).count() as u64
+}
+
+pub fn counted_in_map<const N: usize>(lines: &[Line<N>], k: u32, raster: Raster) -> u64 {
+    let mut points: HashMap<Point<N>, u32> = HashMap::new();
+    lines
+        .iter()
+        .flat_map(|line| line.points(raster))
+        .for_each(|point| *points.entry(point).or_insert(0) += 1);
+    points.values().filter(|count| **count >= k).count() as u64
+}
+
+// The lattice points of a planar line all lie on one support: the points p with
+// sy * p.x - sx * p.y == c, for the primitive direction (sx, sy) of the line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Support {
+    sx: i64,
+    sy: i64,
+    c: i128,
+}
+
+impl Support {
+    // Consecutive lattice points on a support are one unit of `t` apart.
+    fn t(&self, p: Point) -> i64 {
+        if self.sx != 0 {
+            (p.x() as i64).div_euclid(self.sx)
+        } else {
+            (p.y() as i64).div_euclid(self.sy)
+        }
+    }
+
+    fn crossing(&self, other: &Support) -> Option<Point> {
+        let (sx1, sy1, sx2, sy2) = (
+            self.sx as i128,
+            self.sy as i128,
+            other.sx as i128,
+            other.sy as i128,
+        );
+        let det = sx1 * sy2 - sy1 * sx2;
+        let (x, y) = (sx1 * other.c - sx2 * self.c, sy1 * other.c - sy2 * self.c);
+        if det == 0 || x % det != 0 || y % det != 0 {
+            return None;
+        }
+        Some(Point::new(
+            i32::try_from(x / det).ok()?,
+            i32::try_from(y / det).ok()?,
+        ))
+    }
+}
+
+fn support(line: &Line) -> (Support, i64, i64) {
+    let [dx, dy] = line.delta();
+    let (mut sx, mut sy) = match line.steps() {
+        0 => (1, 0),
+        steps => (dx / steps, dy / steps),
+    };
+    if sx < 0 || (sx == 0 && sy < 0) {
+        sx = -sx;
+        sy = -sy;
+    }
+    let c = sy as i128 * line.from.x() as i128 - sx as i128 * line.from.y() as i128;
+    let support = Support { sx, sy, c };
+    let (t0, t1) = (support.t(line.from), support.t(line.to));
+    (support, t0.min(t1), t0.max(t1))
+}
+
+// How many lines cover each stretch of a support: stretch i is
+// [starts[i], starts[i + 1]) and the last one, to infinity, is uncovered.
+struct Coverage {
+    starts: Vec<i64>,
+    counts: Vec<u32>,
+}
+
+impl Coverage {
+    fn sweep(intervals: &[(i64, i64)]) -> Self {
+        let mut events = intervals
+            .iter()
+            .flat_map(|(t0, t1)| [(*t0, 1), (*t1 + 1, -1)])
+            .collect::<Vec<(i64, i32)>>();
+        events.sort_unstable();
+        let mut coverage = Coverage {
+            starts: Vec::new(),
+            counts: Vec::new(),
+        };
+        let mut count = 0;
+        for (t, delta) in events {
+            count += delta;
+            if coverage.starts.last() == Some(&t) {
+                *coverage.counts.last_mut().unwrap() = count as u32;
             } else {
-                let range = if x1 < x2 { x1..(x2 + 1) } else { x2..(x1 + 1) };
-                for i in range {
-                    add_point(&mut points, (i, y1));
+                coverage.starts.push(t);
+                coverage.counts.push(count as u32);
+            }
+        }
+        coverage
+    }
+
+    fn at(&self, t: i64) -> u32 {
+        match self.starts.partition_point(|start| *start <= t) {
+            0 => 0,
+            i => self.counts[i - 1],
+        }
+    }
+
+    fn at_least(&self, k: u32) -> u64 {
+        (1..self.starts.len())
+            .filter(|i| self.counts[i - 1] >= k)
+            .map(|i| (self.starts[i] - self.starts[i - 1]) as u64)
+            .sum()
+    }
+}
+
+// Sweeps every support on its own, then fixes up the points where supports cross:
+// there the coverage adds up, and a point may have been counted once per support.
+// Costs O(L log L) plus O(S²) for the crossings of the S supports, whatever the
+// length of the lines.
+pub fn counted_sparsely(lines: &[Line], k: u32) -> u64 {
+    let mut intervals = HashMap::<Support, Vec<(i64, i64)>>::new();
+    for line in lines {
+        let (support, t0, t1) = support(line);
+        intervals.entry(support).or_default().push((t0, t1));
+    }
+    let supports = intervals
+        .iter()
+        .map(|(support, intervals)| (*support, Coverage::sweep(intervals)))
+        .collect::<Vec<(Support, Coverage)>>();
+
+    let mut count = supports
+        .iter()
+        .map(|(_, coverage)| coverage.at_least(k) as i64)
+        .sum::<i64>();
+    let mut crossings = HashMap::<Point, Vec<usize>>::new();
+    for (i, (a, coverage_a)) in supports.iter().enumerate() {
+        for (j, (b, coverage_b)) in supports.iter().enumerate().skip(i + 1) {
+            if let Some(point) = a.crossing(b) {
+                if coverage_a.at(a.t(point)) > 0 && coverage_b.at(b.t(point)) > 0 {
+                    crossings.entry(point).or_default().extend([i, j]);
                 }
             }
-        });
+        }
+    }
+    for (point, mut through) in crossings {
+        through.sort_unstable();
+        through.dedup();
+        let counts = through
+            .iter()
+            .map(|i| supports[*i].1.at(supports[*i].0.t(point)))
+            .collect::<Vec<u32>>();
+        let counted = counts.iter().filter(|c| **c >= k).count() as i64;
+        count += (counts.iter().sum::<u32>() >= k) as i64 - counted;
+    }
+    count as u64
+}
+
+// The coverage of a vent field, for inspecting it point by point.
+pub struct VentMap<const N: usize = 2> {
+    lines: Vec<Option<Line<N>>>,
+    raster: Raster,
+}
+
+impl<const N: usize> VentMap<N> {
+    // Lines that don't pass the filter are ignored, but keep their index.
+    pub fn new(lines: &[Line<N>], filter: Filter, raster: Raster) -> Self {
+        VentMap {
+            lines: lines
+                .iter()
+                .map(|line| Some(*line).filter(|line| line.passes(filter)))
+                .collect(),
+            raster,
+        }
+    }
+
+    pub fn bounds(&self) -> Option<(Point<N>, Point<N>)> {
+        bounds(
+            &self
+                .lines
+                .iter()
+                .flatten()
+                .copied()
+                .collect::<Vec<Line<N>>>(),
+        )
+    }
 
-    let result = points
-        .values()
-        .fold(0, |acc, x| if *x >= 2 { acc + 1 } else { acc });
-    println!("Day 05 > Part 1: {}", result);
+    // Indices of the lines covering the point.
+    pub fn lines_at(&self, p: Point<N>) -> Vec<usize> {
+        (0..self.lines.len())
+            .filter(|i| matches!(self.lines[*i], Some(line) if line.covers(self.raster, p)))
+            .collect()
+    }
+
+    pub fn coverage(&self, p: Point<N>) -> usize {
+        self.lines_at(p).len()
+    }
+}
+
+// Width and height of [min, max], which is empty unless min is above and left of max.
+fn extent(min: Point, max: Point) -> (usize, usize) {
+    let length = |low: i32, high: i32| (high as i64 - low as i64 + 1).max(0) as usize;
+    (length(min.x(), max.x()), length(min.y(), max.y()))
+}
+
+impl VentMap<2> {
+    // Coverage of every point of [min, max], row by row (none if it's empty).
+    pub fn grid(&self, min: Point, max: Point) -> Vec<Vec<u32>> {
+        let (width, height) = extent(min, max);
+        if width == 0 || height == 0 {
+            return Vec::new();
+        }
+        let mut grid = vec![vec![0_u32; width]; height];
+        for line in self.lines.iter().flatten() {
+            for p in line.points_within(self.raster, min, max) {
+                grid[(p.y() - min.y()) as usize][(p.x() - min.x()) as usize] += 1;
+            }
+        }
+        grid
+    }
+
+    // The highest coverage in [min, max] and the first point (in reading order)
+    // reaching it.
+    pub fn max_in(&self, min: Point, max: Point) -> (u32, Point) {
+        let mut best = (0, min);
+        for (y, row) in self.grid(min, max).iter().enumerate() {
+            for (x, count) in row.iter().enumerate() {
+                if *count > best.0 {
+                    best = (*count, Point::new(min.x() + x as i32, min.y() + y as i32));
+                }
+            }
+        }
+        best
+    }
+
+    // Drawn like the puzzle's diagrams: `.` where no line is, the coverage
+    // otherwise (`+` from 10 lines on).
+    pub fn diagram(&self, min: Point, max: Point) -> String {
+        self.grid(min, max)
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|count| match count {
+                        0 => '.',
+                        1..=9 => char::from_digit(*count, 10).unwrap(),
+                        _ => '+',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    // A binary PGM image, brighter where more lines overlap.
+    pub fn heatmap_pgm(&self, min: Point, max: Point) -> Vec<u8> {
+        let grid = self.grid(min, max);
+        let top = grid.iter().flatten().max().copied().unwrap_or(0).max(1);
+        let (width, height) = (grid.first().map_or(0, Vec::len), grid.len());
+        let mut image = format!("P5\n{} {}\n255\n", width, height).into_bytes();
+        image.extend(grid.iter().flatten().map(|count| (count * 255 / top) as u8));
+        image
+    }
+}
+
+fn min_overlap() -> u32 {
+    param("d05.min_overlap", 2)
+}
+
+fn raster() -> Raster {
+    param("d05.raster", Raster::Exact)
+}
+
+pub fn part1() {
+    let lines: Vec<Line> = parse_lines(&read_file_lines("input/05.txt"));
+
+    answer(
+        5,
+        1,
+        count_covered(&lines, min_overlap(), Filter::Orthogonal, raster()),
+    );
 }
 
 /*
@@ -121,34 +657,198 @@ Consider all of the lines. At how many points do at least two lines overlap?
 */
 
 pub fn part2() {
-    let mut points: HashMap<(i16, i16), i16> = HashMap::new();
-    parse_coords().for_each(|(x1, y1, x2, y2)| {
-        if x1 == x2 {
-            let range = if y1 < y2 { y1..(y2 + 1) } else { y2..(y1 + 1) };
-            for i in range {
-                add_point(&mut points, (x1, i));
-            }
-        } else if y1 == y2 {
-            let range = if x1 < x2 { x1..(x2 + 1) } else { x2..(x1 + 1) };
-            for i in range {
-                add_point(&mut points, (i, y1));
-            }
-        } else {
-            add_point(&mut points, (x1, y1));
-            let step_x = if x1 < x2 { 1 } else { -1 };
-            let step_y = if y1 < y2 { 1 } else { -1 };
-            let mut x = x1;
-            let mut y = y1;
-            while x != x2 && y != y2 {
-                x += step_x;
-                y += step_y;
-                add_point(&mut points, (x, y));
+    let lines: Vec<Line> = parse_lines(&read_file_lines("input/05.txt"));
+    let filter = param("d05.part2_lines", Filter::Diagonal);
+
+    answer(5, 2, count_covered(&lines, min_overlap(), filter, raster()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const EXAMPLE: [&str; 10] = [
+        "0,9 -> 5,9",
+        "8,0 -> 0,8",
+        "9,4 -> 3,4",
+        "2,2 -> 2,1",
+        "7,0 -> 7,4",
+        "6,4 -> 2,0",
+        "0,9 -> 2,9",
+        "3,4 -> 1,4",
+        "0,0 -> 8,8",
+        "5,5 -> 8,2",
+    ];
+
+    fn example() -> Vec<Line> {
+        parse_lines(&EXAMPLE.map(String::from))
+    }
+
+    #[test]
+    fn example_overlaps() {
+        let lines = example();
+        let exact = Raster::Exact;
+        assert_eq!(count_covered(&lines, 2, Filter::Orthogonal, exact), 5);
+        assert_eq!(count_covered(&lines, 2, Filter::Diagonal, exact), 12);
+        assert_eq!(counted_sparsely(&lines, 2), 12);
+        assert_eq!(counted_in_map(&lines, 2, exact), 12);
+        assert_eq!(count_covered(&lines, 3, Filter::Diagonal, exact), 2);
+    }
+
+    #[test]
+    fn example_lines_in_three_dimensions() {
+        let lines = ["0,0,0 -> 4,4,4", "4,0,0 -> 0,4,4", "2,2,0 -> 2,2,4"].map(String::from);
+        let lines: Vec<Line<3>> = parse_lines(&lines);
+        assert_eq!(count_covered(&lines, 3, Filter::Diagonal, Raster::Exact), 1);
+        assert_eq!(
+            count_covered(&lines, 2, Filter::Orthogonal, Raster::Exact),
+            0
+        );
+    }
+
+    #[test]
+    fn example_diagrams() {
+        let lines = example();
+        let orthogonal = VentMap::new(&lines, Filter::Orthogonal, Raster::Exact);
+        let (min, max) = orthogonal.bounds().unwrap();
+        assert_eq!((min, max), (Point::new(0, 0), Point::new(9, 9)));
+        assert_eq!(
+            orthogonal.diagram(min, max),
+            ".......1..\n\
+             ..1....1..\n\
+             ..1....1..\n\
+             .......1..\n\
+             .112111211\n\
+             ..........\n\
+             ..........\n\
+             ..........\n\
+             ..........\n\
+             222111...."
+        );
+        let all = VentMap::new(&lines, Filter::Diagonal, Raster::Exact);
+        assert_eq!(
+            all.diagram(min, max),
+            "1.1....11.\n\
+             .111...2..\n\
+             ..2.1.111.\n\
+             ...1.2.2..\n\
+             .112313211\n\
+             ...1.2....\n\
+             ..1...1...\n\
+             .1.....1..\n\
+             1.......1.\n\
+             222111...."
+        );
+        assert_eq!(
+            all.diagram(Point::new(3, 3), Point::new(6, 5)),
+            "1.2.\n2313\n1.2."
+        );
+    }
+
+    #[test]
+    fn example_map_queries() {
+        let all = VentMap::new(&example(), Filter::Diagonal, Raster::Exact);
+        assert_eq!(all.lines_at(Point::new(4, 4)), [1, 2, 8]);
+        assert_eq!(all.coverage(Point::new(4, 4)), 3);
+        assert_eq!(all.lines_at(Point::new(9, 9)), []);
+        let orthogonal = VentMap::new(&example(), Filter::Orthogonal, Raster::Exact);
+        // Diagonal lines are skipped but keep their index.
+        assert_eq!(orthogonal.lines_at(Point::new(7, 4)), [2, 4]);
+        assert_eq!(orthogonal.lines_at(Point::new(4, 4)), [2]);
+
+        let (min, max) = all.bounds().unwrap();
+        assert_eq!(all.max_in(min, max), (3, Point::new(4, 4)));
+        assert_eq!(orthogonal.max_in(min, max), (2, Point::new(3, 4)));
+        // Nothing covers the lower right corner.
+        let corner = (Point::new(8, 9), Point::new(9, 9));
+        assert_eq!(all.max_in(corner.0, corner.1), (0, Point::new(8, 9)));
+    }
+
+    #[test]
+    fn example_heatmap() {
+        let all = VentMap::new(&example(), Filter::Diagonal, Raster::Exact);
+        let image = all.heatmap_pgm(Point::new(3, 3), Point::new(6, 5));
+        let mut expected = b"P5\n4 3\n255\n".to_vec();
+        expected.extend([85, 0, 170, 0, 170, 255, 85, 255, 85, 0, 170, 0]);
+        assert_eq!(image, expected);
+    }
+
+    #[test]
+    fn empty_regions_have_no_points() {
+        let all = VentMap::new(&example(), Filter::Diagonal, Raster::Exact);
+        let (min, max) = (Point::new(5, 0), Point::new(2, 9));
+        assert!(all.grid(min, max).is_empty());
+        assert_eq!(all.diagram(min, max), "");
+        assert_eq!(all.max_in(min, max), (0, min));
+        assert_eq!(all.heatmap_pgm(min, max), b"P5\n0 0\n255\n");
+        let (min, max) = (
+            Point::new(i32::MAX, i32::MAX),
+            Point::new(i32::MIN, i32::MIN),
+        );
+        assert!(all.grid(min, max).is_empty());
+    }
+
+    // Vent lines at any angle.
+    fn vent_line() -> impl Strategy<Value = Line> {
+        let point = (-50_i32..50, -50_i32..50).prop_map(|(x, y)| Point::new(x, y));
+        (point.clone(), point).prop_map(|(from, to)| Line { from, to })
+    }
+
+    proptest! {
+        #[test]
+        fn rasterized_lines_are_connected_and_exact_where_possible(line in vent_line()) {
+            let points = line.bresenham();
+            let (dx, dy) = ((line.to.x() - line.from.x()).abs(), (line.to.y() - line.from.y()).abs());
+            prop_assert_eq!(points.len() as i32, dx.max(dy) + 1);
+            prop_assert_eq!((points[0], *points.last().unwrap()), (line.from, line.to));
+            prop_assert!(points
+                .windows(2)
+                .all(|w| (w[1].x() - w[0].x()).abs() <= 1 && (w[1].y() - w[0].y()).abs() <= 1));
+            let exact = line.lattice_points();
+            prop_assert!(exact.iter().all(|p| points.contains(p)));
+            if line.passes(Filter::Diagonal) {
+                prop_assert_eq!(exact, points);
             }
         }
-    });
 
-    let result = points
-        .values()
-        .fold(0, |acc, x| if *x >= 2 { acc + 1 } else { acc });
-    println!("Day 05 > Part 2: {}", result);
+        #[test]
+        fn sparse_coverage_counts_match_the_grid(
+            lines in prop::collection::vec(vent_line(), 1..25),
+            k in 1_u32..4,
+        ) {
+            let (min, max) = (Point::new(-50, -50), Point::new(50, 50));
+            let expected = counted_densely(&lines, k, Raster::Exact, min, max);
+            prop_assert_eq!(counted_sparsely(&lines, k), expected);
+            prop_assert_eq!(counted_in_map(&lines, k, Raster::Exact), expected);
+
+            // The same lines in the z = 0 plane of a 3D field.
+            let lift = |p: Point| Point([p.x(), p.y(), 0]);
+            let lifted = lines
+                .iter()
+                .map(|line| Line { from: lift(line.from), to: lift(line.to) })
+                .collect::<Vec<Line<3>>>();
+            prop_assert_eq!(count_covered(&lifted, k, Filter::Any, Raster::Exact), expected);
+        }
+
+        #[test]
+        fn clipped_lines_keep_exactly_the_points_inside(
+            line in vent_line(),
+            (x0, y0, x1, y1) in (-60_i32..60, -60_i32..60, -60_i32..60, -60_i32..60),
+        ) {
+            let (min, max) = (
+                Point::new(x0.min(x1), y0.min(y1)),
+                Point::new(x0.max(x1), y0.max(y1)),
+            );
+            let dot = Line { from: line.from, to: line.from };
+            for line in [line, dot] {
+                let inside = line
+                    .lattice_points()
+                    .into_iter()
+                    .filter(|p| min.x() <= p.x() && p.x() <= max.x() && min.y() <= p.y() && p.y() <= max.y())
+                    .collect::<Vec<Point>>();
+                prop_assert_eq!(line.points_within(Raster::Exact, min, max), inside);
+            }
+        }
+    }
 }
@@ -1,5 +1,9 @@
+use crate::utils::config::param;
 use crate::utils::read_file_lines;
+use crate::utils::report::answer;
+use num_bigint::BigUint;
 use std::collections::HashMap;
+use std::fmt;
 
 /*
 The sea floor is getting steeper. Maybe the sleigh keys got carried this way?
@@ -54,57 +58,336 @@ In this example, after 18 days, there are a total of 26 fish. After 80 days, the
 
 Find a way to simulate lanternfish. How many lanternfish would there be after 80 days?
 */
-fn generate_fishes(days: i64) -> i64 {
-    if days > 0 {
-        days / 7
-    } else {
-        0
+
+// Lanternfish that spawn every `cycle` days, their newborns `newborn_delay` days
+// after birth: 7 and 9 in the puzzle (timers reset to 6, newborns start at 8).
+pub struct Population {
+    cycle: i64,
+    newborn_delay: i64,
+    // Fish descending from a fish with timer 0, after that many days.
+    memo: HashMap<i64, u64>,
+}
+
+impl Population {
+    pub fn new(cycle: i64, newborn_delay: i64) -> Self {
+        assert!(
+            cycle >= 1 && newborn_delay >= 1,
+            "Fish can't spawn more than once a day"
+        );
+        Population {
+            cycle,
+            newborn_delay,
+            memo: HashMap::new(),
+        }
+    }
+
+    pub fn lanternfish() -> Self {
+        Population::new(param("d06.cycle", 7), param("d06.newborn_delay", 9))
+    }
+
+    // A fish with timer 0 spawns on days 1, 1 + cycle, 1 + 2 * cycle... and each
+    // newborn reaches timer 0 `newborn_delay - 1` days later.
+    fn after_zero(&mut self, days: i64) -> u64 {
+        if days <= 0 {
+            return 1;
+        }
+        if let Some(count) = self.memo.get(&days) {
+            return *count;
+        }
+        let count = 1
+            + (1..=days)
+                .step_by(self.cycle as usize)
+                .map(|spawn| self.after_zero(days - spawn - (self.newborn_delay - 1)))
+                .sum::<u64>();
+        self.memo.insert(days, count);
+        count
+    }
+
+    // The fish itself and all its descendants after `days` days.
+    pub fn descendants(&mut self, timer: i64, days: i64) -> u64 {
+        assert!(timer >= 0, "Timers can't be negative");
+        if days <= timer {
+            1
+        } else {
+            self.after_zero(days - timer)
+        }
+    }
+
+    pub fn count(&mut self, timers: &[i64], days: i64) -> u64 {
+        timers
+            .iter()
+            .map(|timer| self.descendants(*timer, days))
+            .sum()
+    }
+
+    // Fish per timer value, from 0 up to the larger of the reset timer, the newborn
+    // timer and the school's own timers.
+    fn buckets(&self, timers: &[i64]) -> Vec<u64> {
+        assert!(
+            timers.iter().all(|timer| *timer >= 0),
+            "Timers can't be negative"
+        );
+        let size = timers
+            .iter()
+            .map(|timer| *timer as usize + 1)
+            .fold(self.cycle.max(self.newborn_delay) as usize, usize::max);
+        let mut buckets = vec![0; size];
+        for timer in timers {
+            buckets[*timer as usize] += 1;
+        }
+        buckets
+    }
+
+    // Fish after `days` days, modulo `modulus` if given. Fish are counted per
+    // timer value, and a day maps those counts through a fixed matrix, so `days`
+    // days are the matrix raised to `days`, in O(log days) multiplications. The
+    // exact count has about `days * log2(growth_rate)` bits: take a modulus for
+    // much more than a few million days.
+    pub fn project(&self, timers: &[i64], days: u64, modulus: Option<&BigUint>) -> BigUint {
+        assert!(modulus != Some(&BigUint::default()), "Can't count modulo 0");
+        let counts = self.buckets(timers);
+        let size = counts.len();
+        // day[t][u]: fish with timer t tomorrow per fish with timer u today.
+        let mut day = Matrix::zero(size);
+        for t in 1..size {
+            day.0[t - 1][t] += 1_u32;
+        }
+        day.0[self.cycle as usize - 1][0] += 1_u32;
+        day.0[self.newborn_delay as usize - 1][0] += 1_u32;
+
+        let after = day.pow(days, modulus);
+        let total = after
+            .0
+            .iter()
+            .flat_map(|row| row.iter().zip(&counts).map(|(n, count)| n * *count))
+            .sum::<BigUint>();
+        match modulus {
+            Some(m) => total % m,
+            None => total,
+        }
+    }
+
+    // The factor by which the population eventually grows every day: the dominant
+    // eigenvalue of the daily matrix, i.e. the root above 1 of
+    // x^-cycle + x^-newborn_delay = 1 (the left side decreases from 2 at x = 1 to at
+    // most 1 at x = 2).
+    pub fn growth_rate(&self) -> f64 {
+        let (cycle, delay) = (self.cycle as i32, self.newborn_delay as i32);
+        let excess = |x: f64| x.powi(-cycle) + x.powi(-delay) - 1.0;
+        let (mut low, mut high) = (1.0_f64, 2.0_f64);
+        for _ in 0..64 {
+            let mid = (low + high) / 2.0;
+            if excess(mid) > 0.0 {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        (low + high) / 2.0
+    }
+
+    // Simulates the school day by day, one bucket of fish per timer value, from the
+    // initial school on day 0.
+    pub fn simulate(&self, timers: &[i64]) -> Simulation {
+        let buckets = self.buckets(timers);
+        Simulation {
+            cycle: self.cycle as usize,
+            newborn_delay: self.newborn_delay as usize,
+            next: Some(Ok(Census {
+                day: 0,
+                total: timers.len() as u64,
+                buckets,
+                born: 0,
+            })),
+        }
+    }
+
+    // Every day up to `days`. Counts overflow u64 after about 450 days of the
+    // puzzle's school: `project` goes further.
+    pub fn history(&self, timers: &[i64], days: u64) -> Result<History, Overflow> {
+        self.simulate(timers)
+            .take_while(|census| match census {
+                Ok(census) => census.day <= days,
+                Err(overflow) => overflow.day <= days,
+            })
+            .collect::<Result<Vec<Census>, Overflow>>()
+            .map(|days| History { days })
+    }
+
+    // The first day, up to `days`, with more than `count` fish. Only simulates up to
+    // that day.
+    pub fn first_day_above(&self, timers: &[i64], count: u64, days: u64) -> Option<u64> {
+        // An empty school never grows.
+        if timers.is_empty() {
+            return None;
+        }
+        self.simulate(timers)
+            .map(|census| match census {
+                Ok(census) => (census.day, census.total > count),
+                Err(overflow) => (overflow.day, true),
+            })
+            .take_while(|(day, _)| *day <= days)
+            .find(|(_, above)| *above)
+            .map(|(day, _)| day)
+    }
+}
+
+// A day on which the school has more than u64::MAX fish.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow {
+    pub day: u64,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "more than {} fish on day {}", u64::MAX, self.day)
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+// The school day after day, until it overflows.
+pub struct Simulation {
+    cycle: usize,
+    newborn_delay: usize,
+    next: Option<Result<Census, Overflow>>,
+}
+
+impl Simulation {
+    fn after(&self, census: &Census) -> Result<Census, Overflow> {
+        let (day, born) = (census.day + 1, census.buckets[0]);
+        // No bucket can overflow if their sum doesn't.
+        let total = census.total.checked_add(born).ok_or(Overflow { day })?;
+        let mut buckets = census.buckets.clone();
+        buckets.rotate_left(1);
+        *buckets.last_mut().unwrap() = 0;
+        buckets[self.cycle - 1] += born;
+        buckets[self.newborn_delay - 1] += born;
+        Ok(Census {
+            day,
+            buckets,
+            total,
+            born,
+        })
+    }
+}
+
+impl Iterator for Simulation {
+    type Item = Result<Census, Overflow>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let census = self.next.take()?;
+        if let Ok(today) = &census {
+            self.next = Some(self.after(today));
+        }
+        Some(census)
     }
 }
 
-fn deep_gen(fish: i64, days: i64, memo: &mut HashMap<(i64, i64), i64>) -> i64 {
-    if memo.contains_key(&(fish, days)) {
-        memo[&(fish, days)]
-    } else {
-        let mut total = 0;
-        for i in 0..fish {
-            let remaining_days = days - 9 - 7 * i;
-            if remaining_days >= 0 {
-                let gen = generate_fishes(remaining_days) + 1;
-                if gen > 0 {
-                    total += gen + deep_gen(gen, remaining_days, memo);
+// A square matrix of (possibly huge) counts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Matrix(Vec<Vec<BigUint>>);
+
+impl Matrix {
+    fn zero(size: usize) -> Self {
+        Matrix(vec![vec![BigUint::default(); size]; size])
+    }
+
+    fn identity(size: usize) -> Self {
+        let mut identity = Matrix::zero(size);
+        for i in 0..size {
+            identity.0[i][i] += 1_u32;
+        }
+        identity
+    }
+
+    fn mul(&self, other: &Matrix, modulus: Option<&BigUint>) -> Matrix {
+        let size = self.0.len();
+        let mut product = Matrix::zero(size);
+        for i in 0..size {
+            for j in 0..size {
+                let mut cell = (0..size)
+                    .map(|k| &self.0[i][k] * &other.0[k][j])
+                    .sum::<BigUint>();
+                if let Some(m) = modulus {
+                    cell %= m;
                 }
+                product.0[i][j] = cell;
+            }
+        }
+        product
+    }
+
+    // Exponentiation by squaring.
+    fn pow(&self, mut exponent: u64, modulus: Option<&BigUint>) -> Matrix {
+        let (mut result, mut base) = (Matrix::identity(self.0.len()), self.clone());
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.mul(&base, modulus);
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.mul(&base, modulus);
+            }
+        }
+        result
+    }
+}
+
+// The school on one day of a simulation: fish per timer value, their total, and
+// the fish born that day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Census {
+    pub day: u64,
+    pub buckets: Vec<u64>,
+    pub total: u64,
+    pub born: u64,
+}
+
+// Every day of a simulation, from the initial school on day 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct History {
+    pub days: Vec<Census>,
+}
+
+impl History {
+    pub fn last(&self) -> &Census {
+        self.days.last().unwrap()
+    }
+
+    // One row per day: the buckets by timer value, the total and the fish born.
+    pub fn to_csv(&self) -> String {
+        let timers = self.days[0].buckets.len();
+        let mut csv = String::from("day,");
+        for timer in 0..timers {
+            csv.push_str(&format!("timer{},", timer));
+        }
+        csv.push_str("total,born\n");
+        for census in &self.days {
+            csv.push_str(&format!("{},", census.day));
+            for count in &census.buckets {
+                csv.push_str(&format!("{},", count));
             }
+            csv.push_str(&format!("{},{}\n", census.total, census.born));
         }
-        memo.insert((fish, days), total);
-        total
+        csv
     }
 }
 
-pub fn count_fish_generations(timer: i64, days: i64, memo: &mut HashMap<(i64, i64), i64>) -> i64 {
-    let mut total = 1;
-    let generated_fishes = generate_fishes(days - timer - 1) + 1;
-    total += generated_fishes;
-    total += deep_gen(generated_fishes, days - timer - 1, memo);
-    total
+pub fn parse_timers(lines: &[String]) -> Vec<i64> {
+    lines
+        .iter()
+        .flat_map(|line| line.split(','))
+        .filter(|timer| !timer.trim().is_empty())
+        .map(|timer| timer.trim().parse().unwrap())
+        .collect()
 }
 
 pub fn part1() {
-    let days = 80;
-    let mut memo: HashMap<(i64, i64), i64> = HashMap::new();
-    let gens = [
-        count_fish_generations(1, days, &mut memo),
-        count_fish_generations(2, days, &mut memo),
-        count_fish_generations(3, days, &mut memo),
-        count_fish_generations(4, days, &mut memo),
-        count_fish_generations(5, days, &mut memo),
-    ];
-
-    let fish = read_file_lines("input/06.txt")[0]
-        .split(',')
-        .map(|timer_str| gens[(timer_str.parse::<i64>().unwrap() - 1) as usize])
-        .collect::<Vec<i64>>();
-    println!("Day 06 > Part 1: {}", fish.iter().sum::<i64>());
+    let days = param("d06.part1_days", 80);
+    let timers = parse_timers(&read_file_lines("input/06.txt"));
+
+    answer(6, 1, Population::lanternfish().project(&timers, days, None));
 }
 
 /*
@@ -116,19 +399,150 @@ How many lanternfish would there be after 256 days?
 */
 
 pub fn part2() {
-    let days = 256;
-    let mut memo: HashMap<(i64, i64), i64> = HashMap::new();
-    let gens = [
-        count_fish_generations(1, days, &mut memo),
-        count_fish_generations(2, days, &mut memo),
-        count_fish_generations(3, days, &mut memo),
-        count_fish_generations(4, days, &mut memo),
-        count_fish_generations(5, days, &mut memo),
-    ];
-
-    let fish = read_file_lines("input/06.txt")[0]
-        .split(',')
-        .map(|timer_str| gens[(timer_str.parse::<i64>().unwrap() - 1) as usize])
-        .collect::<Vec<i64>>();
-    println!("Day 06 > Part 2: {}", fish.iter().sum::<i64>());
+    let days = param("d06.part2_days", 256);
+    let timers = parse_timers(&read_file_lines("input/06.txt"));
+
+    answer(6, 2, Population::lanternfish().project(&timers, days, None));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const EXAMPLE: [i64; 5] = [3, 4, 3, 1, 2];
+
+    #[test]
+    fn example_counts() {
+        let mut population = Population::new(7, 9);
+        assert_eq!(population.count(&EXAMPLE, 18), 26);
+        assert_eq!(population.count(&EXAMPLE, 80), 5934);
+        assert_eq!(
+            population.project(&EXAMPLE, 256, None),
+            BigUint::from(26984457539_u64)
+        );
+    }
+
+    #[test]
+    fn example_history() {
+        let history = Population::new(7, 9).history(&EXAMPLE, 18).unwrap();
+        // After 9 days: 1,2,1,6,0,1,2,3,3,4,8
+        assert_eq!(history.days[9].buckets, [1, 3, 2, 2, 1, 0, 1, 0, 1]);
+        assert_eq!((history.days[9].total, history.days[9].born), (11, 1));
+        assert_eq!(history.last().total, 26);
+    }
+
+    #[test]
+    fn example_history_as_csv() {
+        let history = Population::new(7, 9).history(&EXAMPLE, 2).unwrap();
+        assert_eq!(
+            history.to_csv(),
+            "day,timer0,timer1,timer2,timer3,timer4,timer5,timer6,timer7,timer8,total,born\n\
+             0,0,1,1,2,1,0,0,0,0,5,0\n\
+             1,1,1,2,1,0,0,0,0,0,5,0\n\
+             2,1,2,1,0,0,0,1,0,1,6,1\n"
+        );
+    }
+
+    #[test]
+    fn first_days_above_a_count() {
+        let population = Population::new(7, 9);
+        // 5, 5, 6, 7, 9 and 10 fish on the first days.
+        let first_day_above = |count, days| population.first_day_above(&EXAMPLE, count, days);
+        assert_eq!(first_day_above(4, 1000), Some(0));
+        assert_eq!(first_day_above(5, 1000), Some(2));
+        assert_eq!(first_day_above(8, 1000), Some(4));
+        assert_eq!(first_day_above(9, 4), None);
+        assert_eq!(first_day_above(9, 5), Some(5));
+        assert_eq!(population.first_day_above(&[], 0, u64::MAX), None);
+    }
+
+    #[test]
+    fn histories_stop_at_an_overflow() {
+        let population = Population::new(7, 9);
+        let overflow = population.history(&EXAMPLE, 1000).unwrap_err();
+        assert_eq!(
+            overflow.to_string(),
+            format!(
+                "more than 18446744073709551615 fish on day {}",
+                overflow.day
+            )
+        );
+        let history = population.history(&EXAMPLE, overflow.day - 1).unwrap();
+        let last = history.last();
+        assert!(last.total.checked_add(last.buckets[0]).is_none());
+        assert_eq!(
+            population.first_day_above(&EXAMPLE, u64::MAX, u64::MAX),
+            Some(overflow.day)
+        );
+    }
+
+    #[test]
+    fn growth_rates() {
+        let population = Population::new(7, 9);
+        let rate = population.growth_rate();
+        assert!((rate - 1.0910).abs() < 1e-4, "{}", rate);
+        // Far enough out, each day multiplies the school by the growth rate.
+        let after = |days| population.project(&EXAMPLE, days, None);
+        let ratio = after(2001) * 1_000_000_u32 / after(2000);
+        assert!(u64::try_from(ratio).unwrap().abs_diff((rate * 1e6) as u64) <= 1);
+        // About days * log2(rate) bits, give or take the size of the school.
+        let bits = after(10_000).bits() as f64;
+        assert!((bits / 10_000.0 - rate.log2()).abs() < 1e-3);
+        // x^-3 + x^-3 = 1
+        let rate = Population::new(3, 3).growth_rate();
+        assert!((rate - 2_f64.powf(1.0 / 3.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn projections_to_a_quintillion_days() {
+        let population = Population::new(7, 9);
+        let modulus = BigUint::from(1_000_000_007_u32);
+        let after = |days| population.project(&EXAMPLE, days, Some(&modulus));
+        let days = 10_u64.pow(18);
+        assert!(after(days) < modulus);
+        // Every fish is the one it was 7 days ago, or was born 9 days ago.
+        assert_eq!(after(days), (after(days - 7) + after(days - 9)) % &modulus);
+        assert_eq!(after(256), BigUint::from(26984457539_u64) % &modulus);
+    }
+
+    // A school of fish with any timers, under any cycle and newborn delay.
+    fn school() -> impl Strategy<Value = (Vec<i64>, i64, i64, i64)> {
+        (3_i64..=9, 3_i64..=11).prop_flat_map(|(cycle, delay)| {
+            (
+                prop::collection::vec(0_i64..=cycle.max(delay), 1..10),
+                0_i64..30,
+                Just(cycle),
+                Just(delay),
+            )
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn lanternfish_history_matches_recursion((school, days, cycle, delay) in school()) {
+            let mut population = Population::new(cycle, delay);
+            let history = population.history(&school, days as u64).unwrap();
+            prop_assert_eq!(history.days.len() as i64, days + 1);
+            for (day, census) in history.days.iter().enumerate() {
+                prop_assert_eq!(census.total, population.count(&school, day as i64));
+                prop_assert_eq!(census.buckets.iter().sum::<u64>(), census.total);
+                if day > 0 {
+                    prop_assert_eq!(census.born, census.total - history.days[day - 1].total);
+                }
+            }
+        }
+
+        #[test]
+        fn lanternfish_projection_matches_recursion(
+            (school, days, cycle, delay) in school(),
+            modulus in 1_u32..1000,
+        ) {
+            let mut population = Population::new(cycle, delay);
+            let exact = population.project(&school, days as u64, None);
+            prop_assert_eq!(&exact, &BigUint::from(population.count(&school, days)));
+            let modulus = BigUint::from(modulus);
+            prop_assert_eq!(population.project(&school, days as u64, Some(&modulus)), exact % &modulus);
+        }
+    }
 }
@@ -1,4 +1,5 @@
 use crate::utils::read_file_lines;
+use crate::utils::report::answer;
 
 /*
 A giant whale has decided your submarine is its next meal, and it's much faster than you are. There's nowhere to run!
@@ -35,19 +36,90 @@ This costs a total of 37 fuel. This is the cheapest possible outcome; more expen
 Determine the horizontal position that the crabs can align to using the least fuel possible. How much fuel must they spend to align to that position?
 */
 
-pub fn part1() {
-    let mut crabs_positions = read_file_lines("input/07.txt")[0]
-        .split(',')
-        .map(|x| x.parse().unwrap())
-        .collect::<Vec<i32>>();
-    let median: usize = (crabs_positions.len() + 1) / 2;
-    crabs_positions.sort_unstable();
-    let pos = crabs_positions[median];
+// Fuel a crab burns to move `distance` steps. The cost must be convex and
+// nondecreasing in the distance: then the total fuel is convex in the alignment
+// position, which is what `align` relies on.
+pub trait FuelCost {
+    fn fuel(&self, distance: i64) -> i64;
+}
+
+// Part 1: one fuel per step.
+pub struct Linear;
 
-    let fuel = crabs_positions
+impl FuelCost for Linear {
+    fn fuel(&self, distance: i64) -> i64 {
+        distance
+    }
+}
+
+// Part 2: the n-th step costs n fuel.
+pub struct Triangular;
+
+impl FuelCost for Triangular {
+    fn fuel(&self, distance: i64) -> i64 {
+        distance * (distance + 1) / 2
+    }
+}
+
+pub struct Quadratic;
+
+impl FuelCost for Quadratic {
+    fn fuel(&self, distance: i64) -> i64 {
+        distance * distance
+    }
+}
+
+impl<F: Fn(i64) -> i64> FuelCost for F {
+    fn fuel(&self, distance: i64) -> i64 {
+        self(distance)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alignment {
+    pub position: i64,
+    pub fuel: i64,
+}
+
+pub fn fuel_at(crabs: &[i64], cost: &impl FuelCost, position: i64) -> i64 {
+    crabs
         .iter()
-        .fold(0, |acc, x| acc + i32::abs(x - pos));
-    println!("Day 07 > Part 1: {}", fuel);
+        .map(|crab| cost.fuel((crab - position).abs()))
+        .sum()
+}
+
+// The cheapest alignment, at the leftmost cheapest position between the outermost
+// crabs (none outside them is cheaper). The median and the floored mean only
+// approximate it: an even number of crabs has two medians, and the mean can be one
+// off the triangular optimum. Instead this bisects on the slope of the convex total
+// for the first position where moving right stops saving fuel.
+pub fn align(crabs: &[i64], cost: &impl FuelCost) -> Option<Alignment> {
+    let (mut low, mut high) = (*crabs.iter().min()?, *crabs.iter().max()?);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if fuel_at(crabs, cost, mid + 1) >= fuel_at(crabs, cost, mid) {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    Some(Alignment {
+        position: low,
+        fuel: fuel_at(crabs, cost, low),
+    })
+}
+
+pub fn parse_crabs(lines: &[String]) -> Vec<i64> {
+    lines[0]
+        .split(',')
+        .map(|x| x.trim().parse().unwrap())
+        .collect()
+}
+
+pub fn part1() {
+    let crabs = parse_crabs(&read_file_lines("input/07.txt"));
+
+    answer(7, 1, align(&crabs, &Linear).unwrap().fuel);
 }
 
 /*
@@ -74,21 +146,61 @@ Determine the horizontal position that the crabs can align to using the least fu
 */
 
 pub fn part2() {
-    let mut positions_sum: i32 = 0;
-    let crabs_positions = read_file_lines("input/07.txt")[0]
-        .split(',')
-        .map(|x| {
-            let val = x.parse().unwrap();
-            positions_sum += val;
-            val
-        })
-        .collect::<Vec<i32>>();
-    let avg: i32 = positions_sum / crabs_positions.len() as i32;
-
-    let total_fuel = crabs_positions.iter().fold(0, |acc, crab_pos| {
-        let steps = i32::abs(crab_pos - avg);
-        acc + (steps * (steps + 1) / 2)
-    });
-
-    println!("Day 07 > Part 2: {}", total_fuel);
+    let crabs = parse_crabs(&read_file_lines("input/07.txt"));
+
+    answer(7, 2, align(&crabs, &Triangular).unwrap().fuel);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const EXAMPLE: [i64; 10] = [16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+
+    #[test]
+    fn example_alignments() {
+        let linear = Alignment {
+            position: 2,
+            fuel: 37,
+        };
+        assert_eq!(align(&EXAMPLE, &Linear), Some(linear));
+        assert_eq!(fuel_at(&EXAMPLE, &Linear, 1), 41);
+        assert_eq!(fuel_at(&EXAMPLE, &Linear, 3), 39);
+        assert_eq!(fuel_at(&EXAMPLE, &Linear, 10), 71);
+
+        let triangular = Alignment {
+            position: 5,
+            fuel: 168,
+        };
+        assert_eq!(align(&EXAMPLE, &Triangular), Some(triangular));
+        assert_eq!(fuel_at(&EXAMPLE, &Triangular, 2), 206);
+        assert_eq!(align(&[], &Linear), None);
+    }
+
+    // Crabs, few enough positions apart to try every alignment between them.
+    fn crabs() -> impl Strategy<Value = Vec<i64>> {
+        prop::collection::vec(-50_i64..50, 1..20)
+    }
+
+    fn align_by_brute_force(crabs: &[i64], cost: &impl FuelCost) -> Alignment {
+        (*crabs.iter().min().unwrap()..=*crabs.iter().max().unwrap())
+            .map(|position| Alignment {
+                position,
+                fuel: fuel_at(crabs, cost, position),
+            })
+            .min_by_key(|alignment| (alignment.fuel, alignment.position))
+            .unwrap()
+    }
+
+    proptest! {
+        #[test]
+        fn crab_alignment_is_the_leftmost_optimum(crabs in crabs()) {
+            let dead_zone = |distance: i64| (distance - 3).max(0) * 5;
+            prop_assert_eq!(align(&crabs, &Linear), Some(align_by_brute_force(&crabs, &Linear)));
+            prop_assert_eq!(align(&crabs, &Triangular), Some(align_by_brute_force(&crabs, &Triangular)));
+            prop_assert_eq!(align(&crabs, &Quadratic), Some(align_by_brute_force(&crabs, &Quadratic)));
+            prop_assert_eq!(align(&crabs, &dead_zone), Some(align_by_brute_force(&crabs, &dead_zone)));
+        }
+    }
 }
@@ -1,4 +1,5 @@
 use crate::utils::read_file_lines;
+use crate::utils::report::answer;
 use std::collections::HashMap;
 use std::collections::HashSet;
 /*
@@ -97,7 +98,7 @@ pub fn part1() {
         .collect::<Vec<i32>>()
         .iter()
         .sum();
-    println!("Day 08 > Part 1: {}", result);
+    answer(8, 1, result);
 }
 
 /*
@@ -302,5 +303,5 @@ pub fn part2() {
         })
     });
 
-    println!("Day 08 > Part 2: {}", result);
+    answer(8, 2, result);
 }
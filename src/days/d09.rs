@@ -1,9 +1,21 @@
-use crate::utils::read_file_lines;
+use crate::utils::cache::load;
+use crate::utils::report::answer;
 use std::collections::HashSet;
 
 /*
 */
 
+fn parse_map(lines: &[String]) -> Vec<Vec<i16>> {
+    lines
+        .iter()
+        .map(|row| {
+            row.chars()
+                .map(|c| c.to_string().parse().unwrap())
+                .collect::<Vec<i16>>()
+        })
+        .collect::<Vec<Vec<i16>>>()
+}
+
 const NEIGHBORS: [(i16, i16); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
 
 fn get_neighbors((x, y): (i16, i16), map: &[Vec<i16>]) -> Vec<(i16, i16)> {
@@ -23,14 +35,7 @@ fn get_neighbors((x, y): (i16, i16), map: &[Vec<i16>]) -> Vec<(i16, i16)> {
 }
 
 pub fn part1() {
-    let map = read_file_lines("input/09.txt")
-        .iter()
-        .map(|row| {
-            row.chars()
-                .map(|c| c.to_string().parse().unwrap())
-                .collect::<Vec<i16>>()
-        })
-        .collect::<Vec<Vec<i16>>>();
+    let map = load("input/09.txt", parse_map);
 
     let mut stack = Vec::<(i16, i16)>::from([(0_i16, 0_i16)]);
     let mut visited = HashSet::<(i16, i16)>::new();
@@ -52,7 +57,7 @@ pub fn part1() {
         neighbors.iter().for_each(|n| stack.push(*n));
     }
 
-    println!("Day 09 > Part 1: {}", risk);
+    answer(9, 1, risk);
 }
 
 /*
@@ -76,14 +81,7 @@ fn get_neighbors_but_9((x, y): (i16, i16), map: &[Vec<i16>]) -> Vec<(i16, i16)>
 }
 
 pub fn part2() {
-    let map = read_file_lines("input/09.txt")
-        .iter()
-        .map(|row| {
-            row.chars()
-                .map(|c| c.to_string().parse().unwrap())
-                .collect::<Vec<i16>>()
-        })
-        .collect::<Vec<Vec<i16>>>();
+    let map = load("input/09.txt", parse_map);
 
     let mut visited = HashSet::<(i16, i16)>::new();
     let mut sizes = Vec::<i16>::new();
@@ -116,5 +114,5 @@ pub fn part2() {
         .take(3)
         .fold(1, |acc, v| acc * (*v as i32));
 
-    println!("Day 09 > Part 2: {}", result);
+    answer(9, 2, result);
 }
@@ -1,4 +1,5 @@
 use crate::utils::read_file_lines;
+use crate::utils::report::answer;
 use std::collections::HashMap;
 
 /*
@@ -87,7 +88,7 @@ pub fn part1() {
             acc + points
         });
 
-    println!("Day 10 > Part 1: {}", result);
+    answer(10, 1, result);
 }
 
 /*
@@ -172,5 +173,5 @@ pub fn part2() {
     scores.sort_unstable();
     let result = scores[scores.len() / 2];
 
-    println!("Day 10 > Part 2: {}", result);
+    answer(10, 2, result);
 }
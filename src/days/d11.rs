@@ -1,4 +1,6 @@
-use crate::utils::read_file_lines;
+use crate::utils::cache::load;
+use crate::utils::config::param;
+use crate::utils::report::answer;
 use std::collections::HashSet;
 
 /*
@@ -328,6 +330,17 @@ fn get_neighbors((x, y): (usize, usize), map: &[Vec<i8>]) -> Vec<(usize, usize)>
     neighbors
 }
 
+fn parse_octopuses(lines: &[String]) -> Vec<Vec<i8>> {
+    lines
+        .iter()
+        .map(|line| {
+            line.chars()
+                .map(|c| c.to_string().parse::<i8>().unwrap())
+                .collect::<Vec<i8>>()
+        })
+        .collect::<Vec<Vec<i8>>>()
+}
+
 fn increase(octopuses: &mut Vec<Vec<i8>>) {
     for row in octopuses {
         for octopus in row {
@@ -377,15 +390,8 @@ fn reset(octopuses: &mut Vec<Vec<i8>>) -> usize {
 }
 
 pub fn part1() {
-    let mut octopuses = read_file_lines("input/11.txt")
-        .iter()
-        .map(|line| {
-            line.chars()
-                .map(|c| c.to_string().parse::<i8>().unwrap())
-                .collect::<Vec<i8>>()
-        })
-        .collect::<Vec<Vec<i8>>>();
-    let steps = 100;
+    let mut octopuses = load("input/11.txt", parse_octopuses);
+    let steps: u32 = param("d11.steps", 100);
 
     let mut flashes = 0;
     for _ in 0..steps {
@@ -394,7 +400,7 @@ pub fn part1() {
         flashes += reset(&mut octopuses);
     }
 
-    println!("Day 11 > Part 1: {}", flashes);
+    answer(11, 1, flashes);
 }
 
 /*
@@ -442,14 +448,7 @@ If you can calculate the exact moments when the octopuses will all flash simulta
 */
 
 pub fn part2() {
-    let mut octopuses = read_file_lines("input/11.txt")
-        .iter()
-        .map(|line| {
-            line.chars()
-                .map(|c| c.to_string().parse::<i8>().unwrap())
-                .collect::<Vec<i8>>()
-        })
-        .collect::<Vec<Vec<i8>>>();
+    let mut octopuses = load("input/11.txt", parse_octopuses);
 
     let total = octopuses.len() * octopuses[0].len();
 
@@ -462,5 +461,5 @@ pub fn part2() {
         flashed = reset(&mut octopuses);
     }
 
-    println!("Day 11 > Part 2: {}", step);
+    answer(11, 2, step);
 }
@@ -1,4 +1,5 @@
-use crate::utils::read_file_lines;
+use crate::utils::cache::{load, Cached};
+use crate::utils::report::answer;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
@@ -102,9 +103,34 @@ start-RW
 
 How many paths through this cave system are there that visit small caves at most once?
 */
-fn parse_segments() -> HashMap<String, Vec<String>> {
-    let mut segments = HashMap::<String, Vec<String>>::new();
-    read_file_lines("input/12.txt").iter().for_each(|line| {
+type Segments = HashMap<String, Vec<String>>;
+
+impl Cached for Segments {
+    fn encode(&self) -> String {
+        self.iter()
+            .map(|(from, to)| format!("{}:{}", from, to.join(",")))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn decode(data: &str) -> Option<Self> {
+        data.lines()
+            .map(|line| {
+                let (from, to) = line.split_once(':')?;
+                let to = to
+                    .split(',')
+                    .filter(|cave| !cave.is_empty())
+                    .map(|cave| cave.to_string())
+                    .collect::<Vec<String>>();
+                Some((from.to_string(), to))
+            })
+            .collect()
+    }
+}
+
+fn parse_segments(lines: &[String]) -> Segments {
+    let mut segments = Segments::new();
+    lines.iter().for_each(|line| {
         let mut chunks = line.split('-');
         let from = chunks.next().unwrap();
         let to = chunks.next().unwrap();
@@ -150,9 +176,9 @@ fn visit(
 }
 
 pub fn part1() {
-    let segments = parse_segments();
+    let segments = load("input/12.txt", parse_segments);
     let count = visit("start", &segments, &mut HashSet::<String>::new());
-    println!("Day 12 > Part 1: {}", count);
+    answer(12, 1, count);
 }
 
 /*
@@ -235,7 +261,7 @@ fn visit_2(
 }
 
 pub fn part2() {
-    let segments = parse_segments();
+    let segments = load("input/12.txt", parse_segments);
     let count = visit_2("start", &segments, &mut HashMap::<String, i8>::new());
-    println!("Day 12 > Part 2: {}", count);
+    answer(12, 2, count);
 }
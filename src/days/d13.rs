@@ -1,5 +1,6 @@
-use crate::utils::read_file_lines;
-use crate::utils::print_matrix;
+use crate::utils::cache::{load, Cached};
+use crate::utils::format_matrix;
+use crate::utils::report::answer;
 use std::collections::HashSet;
 
 /*
@@ -113,13 +114,52 @@ The transparent paper is pretty big, so for now, focus on just completing the fi
 How many dots are visible after completing just the first fold instruction on your transparent paper?
 */
 
+type Manual = (HashSet<(usize, usize)>, Vec<(String, usize)>);
+
+// The dots as `x y` pairs on the first line, then one fold per line.
+impl Cached for Manual {
+    const FORMAT: u32 = 2;
+
+    fn encode(&self) -> String {
+        let (dots, instructions) = self;
+        let mut lines = vec![dots
+            .iter()
+            .map(|(x, y)| format!("{} {}", x, y))
+            .collect::<Vec<String>>()
+            .join(" ")];
+        instructions
+            .iter()
+            .for_each(|(axis, pos)| lines.push(format!("{} {}", axis, pos)));
+        lines.join("\n")
+    }
+
+    fn decode(data: &str) -> Option<Self> {
+        let mut lines = data.split('\n');
+        let coords = lines
+            .next()?
+            .split_whitespace()
+            .map(|x| x.parse().ok())
+            .collect::<Option<Vec<usize>>>()?;
+        if coords.len() % 2 != 0 {
+            return None;
+        }
+        let dots = coords.chunks(2).map(|dot| (dot[0], dot[1])).collect();
+        let instructions = lines
+            .map(|line| {
+                let (axis, pos) = line.split_once(' ')?;
+                (axis == "x" || axis == "y").then_some((axis.to_string(), pos.parse().ok()?))
+            })
+            .collect::<Option<Vec<(String, usize)>>>()?;
+        Some((dots, instructions))
+    }
+}
+
 #[allow(clippy::single_char_pattern)]
-#[allow(clippy::type_complexity)]
-fn parse_instructions() -> (HashSet<(usize, usize)>, Vec<(String, usize)>) {
+fn parse_instructions(lines: &[String]) -> Manual {
     let mut dots = HashSet::<(usize, usize)>::new();
     let mut instructions = Vec::<(String, usize)>::new();
     let mut dots_done = false;
-    read_file_lines("input/13.txt").iter().for_each(|line| {
+    lines.iter().for_each(|line| {
         if dots_done {
             let mut chunks = line.split("fold along ");
             chunks.next();
@@ -145,7 +185,7 @@ fn parse_instructions() -> (HashSet<(usize, usize)>, Vec<(String, usize)>) {
     (dots, instructions)
 }
 
-fn fold(dots: HashSet<(usize, usize)>, axis: &str, pos: &usize) -> HashSet<(usize, usize)> {
+pub(crate) fn fold(dots: HashSet<(usize, usize)>, axis: &str, pos: &usize) -> HashSet<(usize, usize)> {
     dots.iter()
         .map(|(x, y)| {
             let reflected: (usize, usize);
@@ -168,9 +208,9 @@ fn fold(dots: HashSet<(usize, usize)>, axis: &str, pos: &usize) -> HashSet<(usiz
 }
 
 pub fn part1() {
-    let (dots, instructions) = parse_instructions();
+    let (dots, instructions) = load("input/13.txt", parse_instructions);
     let (axis, pos) = &instructions[0];
-    println!("Day 13 > Part 1: {:?}", fold(dots, axis, pos).len());
+    answer(13, 1, fold(dots, axis, pos).len());
 }
 
 /*
@@ -180,7 +220,7 @@ What code do you use to activate the infrared thermal imaging camera system?
 */
 
 pub fn part2() {
-    let (mut dots, instructions) = parse_instructions();
+    let (mut dots, instructions) = load("input/13.txt", parse_instructions);
 
     for (axis, pos) in &instructions {
         dots = fold(dots, axis, pos);
@@ -194,6 +234,5 @@ pub fn part2() {
         matrix[*y][*x] = 'X';
     }
 
-    println!("Day 13 > Part 2");
-    print_matrix(&matrix);
+    answer(13, 2, format_matrix(&matrix));
 }
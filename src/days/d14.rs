@@ -1,4 +1,7 @@
+use crate::utils::config::param;
 use crate::utils::read_file_lines;
+use crate::utils::report::answer;
+use num_bigint::BigUint;
 use std::collections::HashMap;
 
 /*
@@ -55,14 +58,15 @@ Apply 10 steps of pair insertion to the polymer template and find the most and l
 */
 
 type Pair = (char, char);
-type Count = HashMap<char, i64>;
+// The polymer roughly doubles with every step, past any fixed-width integer.
+type Count = HashMap<char, BigUint>;
 type Rules = HashMap<Pair, char>;
-type Memo = HashMap<(Pair, i8), Count>;
+type Memo = HashMap<(Pair, u32), Count>;
 
-fn step(pair: &Pair, remaining_steps: i8, count: &mut Count, rules: &Rules, memo: &mut Memo) {
+fn step(pair: &Pair, remaining_steps: u32, count: &mut Count, rules: &Rules, memo: &mut Memo) {
     if let Some(found) = memo.get(&(*pair, remaining_steps)) {
         for (key, value) in found.iter() {
-            *count.entry(*key).or_insert(0) += value;
+            *count.entry(*key).or_default() += value;
         }
         return;
     }
@@ -70,7 +74,7 @@ fn step(pair: &Pair, remaining_steps: i8, count: &mut Count, rules: &Rules, memo
         return;
     }
     let expansion = rules[pair];
-    *count.entry(expansion).or_insert(0) += 1;
+    *count.entry(expansion).or_default() += 1_u32;
 
     let left_pair = (pair.0, expansion);
     let right_pair = (expansion, pair.1);
@@ -96,24 +100,16 @@ fn step(pair: &Pair, remaining_steps: i8, count: &mut Count, rules: &Rules, memo
     memo.insert((right_pair, remaining_steps - 1), right_count.clone());
 
     for (key, value) in left_count.iter() {
-        *count.entry(*key).or_insert(0) += value;
+        *count.entry(*key).or_default() += value;
     }
     for (key, value) in right_count.iter() {
-        *count.entry(*key).or_insert(0) += value;
+        *count.entry(*key).or_default() += value;
     }
 }
 
-fn find_min_max(total: &Count) -> (i64, i64) {
-    let mut min = i64::MAX;
-    let mut max = i64::MIN;
-    for (_, value) in total.iter() {
-        if *value < min {
-            min = *value;
-        }
-        if *value > max {
-            max = *value;
-        }
-    }
+fn find_min_max(total: &Count) -> (BigUint, BigUint) {
+    let min = total.values().min().cloned().unwrap_or_default();
+    let max = total.values().max().cloned().unwrap_or_default();
     (min, max)
 }
 
@@ -132,14 +128,14 @@ fn parse() -> (String, Rules) {
     (start.to_string(), rules)
 }
 
-fn solve(steps: i8) -> i64 {
+fn solve(steps: u32) -> BigUint {
     let (start, rules) = parse();
     let mut total = Count::new();
     let mut first: char = '?';
     let mut second: char = '?';
     let mut pairs = Vec::<Pair>::new();
     start.chars().for_each(|c| {
-        *total.entry(c).or_insert(0) += 1;
+        *total.entry(c).or_default() += 1_u32;
         if first == '?' {
             first = c;
             return;
@@ -153,7 +149,7 @@ fn solve(steps: i8) -> i64 {
         let mut count = Count::new();
         step(pair, steps, &mut count, &rules, &mut memo);
         for (key, value) in count.iter() {
-            *total.entry(*key).or_insert(0) += value;
+            *total.entry(*key).or_default() += value;
         }
     });
 
@@ -162,7 +158,7 @@ fn solve(steps: i8) -> i64 {
 }
 
 pub fn part1() {
-    println!("Day 14 > Part 1: {}", solve(10));
+    answer(14, 1, solve(param("d14.part1_steps", 10)));
 }
 
 /*
@@ -173,5 +169,5 @@ In the above example, the most common element is B (occurring 2192039569602 time
 Apply 40 steps of pair insertion to the polymer template and find the most and least common elements in the result. What do you get if you take the quantity of the most common element and subtract the quantity of the least common element?
 */
 pub fn part2() {
-    println!("Day 14 > Part 2: {}", solve(40));
+    answer(14, 2, solve(param("d14.part2_steps", 40)));
 }
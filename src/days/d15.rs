@@ -1,4 +1,6 @@
-use crate::utils::read_file_lines;
+use crate::utils::cache::load;
+use crate::utils::config::param;
+use crate::utils::report::answer;
 use comparator::collections::BinaryHeap;
 use comparator::comparing;
 
@@ -38,6 +40,17 @@ The total risk of this path is 40 (the starting position is never entered, so it
 What is the lowest total risk of any path from the top left to the bottom right?
 */
 
+fn parse_cavern(lines: &[String]) -> Vec<Vec<i32>> {
+    lines
+        .iter()
+        .map(|line| {
+            line.chars()
+                .map(|c| c.to_digit(10).unwrap() as i32)
+                .collect::<Vec<i32>>()
+        })
+        .collect::<Vec<Vec<i32>>>()
+}
+
 fn get_neighbors(index: i32, dim: usize) -> Vec<usize> {
     let mut neighbors = Vec::<usize>::new();
     let side = (dim as f32).sqrt() as i32;
@@ -67,7 +80,7 @@ struct Node {
     risk: i32,
 }
 
-fn dijkstra(cavern: &[i32]) -> (Vec<i32>, Vec<i32>) {
+pub(crate) fn dijkstra(cavern: &[i32]) -> (Vec<i32>, Vec<i32>) {
     let dim = cavern.len();
     let mut distances = vec![i32::MAX; cavern.len()];
     let mut previous = vec![-1_i32; cavern.len()];
@@ -95,21 +108,14 @@ fn dijkstra(cavern: &[i32]) -> (Vec<i32>, Vec<i32>) {
 }
 
 pub fn part1() {
-    let cavern = read_file_lines("input/15.txt")
-        .iter()
-        .flat_map(|line| {
-            line.chars()
-                .map(|c| c.to_digit(10).unwrap() as i32)
-                .collect::<Vec<i32>>()
-        })
+    let cavern = load("input/15.txt", parse_cavern)
+        .into_iter()
+        .flatten()
         .collect::<Vec<i32>>();
 
     let (distances, _previous) = dijkstra(&cavern);
 
-    println!(
-        "Day 15 > Part 1: Minimum risk = {}",
-        distances.last().unwrap()
-    );
+    answer(15, 1, distances.last().unwrap());
 
     // let side = (dim as f32).sqrt() as usize;
     // let mut prev = *previous.last().unwrap();
@@ -251,18 +257,15 @@ Using the full map, what is the lowest total risk of any path from the top left
 */
 
 pub fn part2() {
-    let extended_cavern_multi_row = read_file_lines("input/15.txt")
-        .iter()
-        .flat_map(|line| {
-            let segment = line
-                .chars()
-                .map(|c| c.to_digit(10).unwrap() as i32)
-                .collect::<Vec<i32>>();
+    let tiles = param("d15.tiles", 5);
+    let extended_cavern_multi_row = load("input/15.txt", parse_cavern)
+        .into_iter()
+        .flat_map(|segment| {
             let mut new_segments = segment.clone();
-            for i in 1..5 {
+            for i in 1..tiles {
                 segment
                     .iter()
-                    .map(|d| if d + i > 9 { d + i - 9 } else { d + i })
+                    .map(|d| (d + i - 1) % 9 + 1)
                     .collect::<Vec<i32>>()
                     .iter()
                     .for_each(|d| new_segments.push(*d));
@@ -272,10 +275,10 @@ pub fn part2() {
         .collect::<Vec<i32>>();
 
     let mut cavern = extended_cavern_multi_row.clone();
-    for i in 1..5 {
+    for i in 1..tiles {
         extended_cavern_multi_row
             .iter()
-            .map(|d| if d + i > 9 { d + i - 9 } else { d + i })
+            .map(|d| (d + i - 1) % 9 + 1)
             .collect::<Vec<i32>>()
             .iter()
             .for_each(|d| cavern.push(*d));
@@ -283,8 +286,5 @@ pub fn part2() {
 
     let (distances, _previous) = dijkstra(&cavern);
 
-    println!(
-        "Day 15 > Part 2: Minimum risk = {}",
-        distances.last().unwrap()
-    );
+    answer(15, 2, distances.last().unwrap());
 }
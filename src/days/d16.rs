@@ -1,4 +1,5 @@
 use crate::utils::read_file_lines;
+use crate::utils::report::answer;
 use bitstream_io::{BigEndian, BitReader};
 use std::io::Cursor;
 
@@ -193,8 +194,9 @@ fn parse_packet(reader: &mut Reader, versions: &mut Vec<u32>, results: &mut Vec<
     }
 }
 
-pub fn part1() {
-    let tmp = read_file_lines("input/16.txt")[0]
+// Returns the sum of all packet versions and the value of the outermost packet.
+pub(crate) fn decode(transmission: &str) -> (u32, i64) {
+    let tmp = transmission
         .chars()
         .map(|c| c.to_digit(16).unwrap() as u8)
         .collect::<Vec<u8>>();
@@ -214,8 +216,13 @@ pub fn part1() {
     let mut results = Vec::<i64>::new();
     parse_packet(&mut reader, &mut versions, &mut results, false);
 
-    println!("Day 16 > Part 1: {}", versions.into_iter().sum::<u32>());
-    println!("Day 16 > Part 2: {}", results[0]);
+    (versions.into_iter().sum::<u32>(), results[0])
+}
+
+pub fn part1() {
+    let (versions, _result) = decode(&read_file_lines("input/16.txt")[0]);
+
+    answer(16, 1, versions);
 }
 
 /*
@@ -248,5 +255,7 @@ What do you get if you evaluate the expression represented by your hexadecimal-e
 */
 
 pub fn part2() {
-    // Solved in part1()
+    let (_versions, result) = decode(&read_file_lines("input/16.txt")[0]);
+
+    answer(16, 2, result);
 }
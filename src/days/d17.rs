@@ -1,4 +1,6 @@
+use crate::utils::config::param;
 use crate::utils::read_file_lines;
+use crate::utils::report::answer;
 use regex::Regex;
 
 /*
@@ -119,7 +121,7 @@ pub fn part1() {
 
     let n = -y1 - 1;
     let max_height = n * (n + 1) / 2;
-    println!("Day 17 > Part 1: {}", max_height);
+    answer(17, 1, max_height);
 }
 
 /*
@@ -162,7 +164,7 @@ pub fn part2() {
     }
 
     let mut count = 0;
-    for initial_y in y1..100 {
+    for initial_y in y1..param("d17.max_initial_y", 100) {
         for initial_x in 0..=x2 {
             let mut x = 0_i32;
             let mut y = 0_i32;
@@ -185,5 +187,5 @@ pub fn part2() {
         }
     }
 
-    println!("Day 17 > Part 2: {}", count);
+    answer(17, 2, count);
 }
@@ -1,4 +1,5 @@
 use crate::utils::read_file_lines;
+use crate::utils::report::answer;
 
 /*
 You descend into the ocean trench and encounter some snailfish. They say they saw the sleigh keys! They'll even tell you which direction the keys went if you help one of the smaller snailfish with his math homework.
@@ -164,9 +165,9 @@ Add up all of the snailfish numbers from the homework assignment in the order th
 */
 
 #[derive(Debug, Clone)]
-struct Snailfish {
-  value: u32,
-  depth: i8,
+pub(crate) struct Snailfish {
+  pub(crate) value: u32,
+  pub(crate) depth: i8,
 }
 
 fn _print(l: &[Snailfish]) {
@@ -174,7 +175,7 @@ fn _print(l: &[Snailfish]) {
   println!()
 }
 
-fn parse(pair: &str) -> Vec<Snailfish> {
+pub(crate) fn parse(pair: &str) -> Vec<Snailfish> {
   let mut result = Vec::<Snailfish>::new();
   let mut depth = -1;
   for c in pair.chars() {
@@ -191,7 +192,7 @@ fn parse(pair: &str) -> Vec<Snailfish> {
   result
 }
 
-fn add(l1: &[Snailfish], l2: &[Snailfish]) -> Vec<Snailfish> {
+pub(crate) fn add(l1: &[Snailfish], l2: &[Snailfish]) -> Vec<Snailfish> {
   let mut result = l1
     .iter()
     .map(|s| Snailfish {
@@ -250,7 +251,7 @@ fn split(l: &mut Vec<Snailfish>) -> bool {
   }
 }
 
-fn reduce(l: &mut Vec<Snailfish>) {
+pub(crate) fn reduce(l: &mut Vec<Snailfish>) {
   let mut change = true;
   while change {
     while explode(l) {}
@@ -299,7 +300,7 @@ pub fn part1() {
   // println!("Result:");
   // print(&result);
 
-  println!("Day 18 > Part 1: {}", calculate_magnitude(&mut result));
+  answer(18, 1, calculate_magnitude(&mut result));
 }
 /*
 You notice a second question on the back of the homework assignment:
@@ -349,5 +350,5 @@ pub fn part2() {
     }
   }
 
-  println!("Day 18 > Part 2: {}", largest_magnitude);
+  answer(18, 2, largest_magnitude);
 }
@@ -1,2 +1,5 @@
 pub mod utils;
-pub mod days;
\ No newline at end of file
+pub mod days;
+
+#[cfg(test)]
+mod properties;
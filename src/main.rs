@@ -1,40 +1,57 @@
 use advent_of_code_2021::days::*;
+use advent_of_code_2021::utils::config;
+use advent_of_code_2021::utils::report::{self, run};
+use std::process;
+
+#[cfg(feature = "alloc-profile")]
+use advent_of_code_2021::utils::alloc::CountingAllocator;
+
+#[cfg(feature = "alloc-profile")]
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
 
 fn main() {
-    d01::part1();
-    d01::part2();
-    d02::part1();
-    d02::part2();
-    d03::part1();
-    d03::part2();
-    d04::part1();
-    d04::part2();
-    d05::part1();
-    d05::part2();
-    d06::part1();
-    d06::part2();
-    d07::part1();
-    d07::part2();
-    d08::part1();
-    d08::part2();
-    d09::part1();
-    d09::part2();
-    d10::part1();
-    d10::part2();
-    d11::part1();
-    d11::part2();
-    d12::part1();
-    d12::part2();
-    d13::part1();
-    d13::part2();
-    d14::part1();
-    d14::part2();
-    d15::part1();
-    d15::part2();
-    d16::part1();
-    d16::part2();
-    d17::part1();
-    d17::part2();
-    d18::part1();
-    d18::part2();
+    if let Err(error) = config::init(std::env::args().skip(1)) {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+
+    run(1, 1, d01::part1);
+    run(1, 2, d01::part2);
+    run(2, 1, d02::part1);
+    run(2, 2, d02::part2);
+    run(3, 1, d03::part1);
+    run(3, 2, d03::part2);
+    run(4, 1, d04::part1);
+    run(4, 2, d04::part2);
+    run(5, 1, d05::part1);
+    run(5, 2, d05::part2);
+    run(6, 1, d06::part1);
+    run(6, 2, d06::part2);
+    run(7, 1, d07::part1);
+    run(7, 2, d07::part2);
+    run(8, 1, d08::part1);
+    run(8, 2, d08::part2);
+    run(9, 1, d09::part1);
+    run(9, 2, d09::part2);
+    run(10, 1, d10::part1);
+    run(10, 2, d10::part2);
+    run(11, 1, d11::part1);
+    run(11, 2, d11::part2);
+    run(12, 1, d12::part1);
+    run(12, 2, d12::part2);
+    run(13, 1, d13::part1);
+    run(13, 2, d13::part2);
+    run(14, 1, d14::part1);
+    run(14, 2, d14::part2);
+    run(15, 1, d15::part1);
+    run(15, 2, d15::part2);
+    run(16, 1, d16::part1);
+    run(16, 2, d16::part2);
+    run(17, 1, d17::part1);
+    run(17, 2, d17::part2);
+    run(18, 1, d18::part1);
+    run(18, 2, d18::part2);
+
+    report::print_summary();
 }
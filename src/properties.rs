@@ -0,0 +1,256 @@
+// Invariants of the core algorithms, checked against generated inputs.
+use crate::days::{d06, d13, d15, d16, d18};
+use proptest::prelude::*;
+use std::collections::HashSet;
+
+// Day 06: a school of fish with any timers, under any cycle and newborn delay.
+// Fast-breeding schools are kept short enough for the naive simulation.
+
+fn school() -> impl Strategy<Value = (Vec<i64>, i64, i64, i64)> {
+    (3_i64..=9, 3_i64..=11).prop_flat_map(|(cycle, delay)| {
+        (
+            prop::collection::vec(0_i64..=cycle.max(delay), 1..10),
+            0_i64..30,
+            Just(cycle),
+            Just(delay),
+        )
+    })
+}
+
+fn simulate_naively(school: &[i64], days: i64, cycle: i64, delay: i64) -> u64 {
+    let mut fish = school.to_vec();
+    for _ in 0..days {
+        let mut newborn = 0;
+        for timer in fish.iter_mut() {
+            if *timer == 0 {
+                *timer = cycle - 1;
+                newborn += 1;
+            } else {
+                *timer -= 1;
+            }
+        }
+        fish.resize(fish.len() + newborn, delay - 1);
+    }
+    fish.len() as u64
+}
+
+// Day 13: a fold line and dots on both sides of it (but never on it).
+
+fn dots_around_fold() -> impl Strategy<Value = (String, usize, HashSet<(usize, usize)>)> {
+    (prop_oneof![Just("x"), Just("y")], 1_usize..20).prop_flat_map(|(axis, pos)| {
+        let dot = (0..=2 * pos, 0..=2 * pos).prop_filter(
+            "dots are never on the fold line",
+            move |(x, y)| {
+                if axis == "x" {
+                    *x != pos
+                } else {
+                    *y != pos
+                }
+            },
+        );
+        (
+            Just(axis.to_string()),
+            Just(pos),
+            prop::collection::hash_set(dot, 0..50),
+        )
+    })
+}
+
+// Day 15: a square cavern plus a random monotone (right/down) path through it.
+
+fn cavern_with_path() -> impl Strategy<Value = (usize, Vec<i32>, Vec<bool>)> {
+    (2_usize..12).prop_flat_map(|side| {
+        let mut moves = vec![true; side - 1];
+        moves.extend(vec![false; side - 1]);
+        (
+            Just(side),
+            prop::collection::vec(1_i32..=9, side * side),
+            Just(moves).prop_shuffle(),
+        )
+    })
+}
+
+// Day 16: BITS packets whose evaluation fits in an `i64`.
+
+#[derive(Debug, Clone)]
+enum Packet {
+    Literal(u8, u64),
+    Operator(u8, u8, bool, Vec<Packet>),
+}
+
+fn packet() -> impl Strategy<Value = Packet> {
+    let literal =
+        (0_u8..8, 0_u64..1_000_000).prop_map(|(version, value)| Packet::Literal(version, value));
+    let packet = literal.prop_recursive(3, 24, 3, |inner| {
+        prop_oneof![
+            (
+                0_u8..8,
+                0_u8..4,
+                any::<bool>(),
+                prop::collection::vec(inner.clone(), 1..4)
+            )
+                .prop_map(|(version, id, by_count, children)| Packet::Operator(
+                    version, id, by_count, children
+                )),
+            (
+                0_u8..8,
+                5_u8..8,
+                any::<bool>(),
+                prop::collection::vec(inner, 2)
+            )
+                .prop_map(|(version, id, by_count, children)| Packet::Operator(
+                    version, id, by_count, children
+                )),
+        ]
+    });
+    packet.prop_filter("evaluation overflows", |packet| evaluate(packet).is_some())
+}
+
+fn push_bits(bits: &mut Vec<bool>, value: u64, width: usize) {
+    (0..width)
+        .rev()
+        .for_each(|i| bits.push(value >> i & 1 == 1));
+}
+
+fn encode_packet(packet: &Packet, bits: &mut Vec<bool>) {
+    match packet {
+        Packet::Literal(version, value) => {
+            push_bits(bits, *version as u64, 3);
+            push_bits(bits, 4, 3);
+            let groups = (0..16)
+                .rev()
+                .skip_while(|i| i > &0 && value >> (i * 4) == 0);
+            let groups = groups.collect::<Vec<u64>>();
+            for (n, i) in groups.iter().enumerate() {
+                bits.push(n + 1 < groups.len());
+                push_bits(bits, value >> (i * 4) & 0xf, 4);
+            }
+        }
+        Packet::Operator(version, id, by_count, children) => {
+            push_bits(bits, *version as u64, 3);
+            push_bits(bits, *id as u64, 3);
+            let mut sub_packets = Vec::<bool>::new();
+            children
+                .iter()
+                .for_each(|child| encode_packet(child, &mut sub_packets));
+            if *by_count {
+                bits.push(true);
+                push_bits(bits, children.len() as u64, 11);
+            } else {
+                bits.push(false);
+                push_bits(bits, sub_packets.len() as u64, 15);
+            }
+            bits.extend(sub_packets);
+        }
+    }
+}
+
+fn encode(packet: &Packet) -> String {
+    let mut bits = Vec::<bool>::new();
+    encode_packet(packet, &mut bits);
+    bits.chunks(4)
+        .map(|chunk| {
+            let nibble = (0..4).fold(0, |acc, i| {
+                acc << 1 | *chunk.get(i).unwrap_or(&false) as u32
+            });
+            std::char::from_digit(nibble, 16)
+                .unwrap()
+                .to_ascii_uppercase()
+        })
+        .collect()
+}
+
+fn version_sum(packet: &Packet) -> u32 {
+    match packet {
+        Packet::Literal(version, _) => *version as u32,
+        Packet::Operator(version, _, _, children) => {
+            *version as u32 + children.iter().map(version_sum).sum::<u32>()
+        }
+    }
+}
+
+fn evaluate(packet: &Packet) -> Option<i64> {
+    match packet {
+        Packet::Literal(_, value) => Some(*value as i64),
+        Packet::Operator(_, id, _, children) => {
+            let values = children
+                .iter()
+                .map(evaluate)
+                .collect::<Option<Vec<i64>>>()?;
+            match id {
+                0 => values.iter().try_fold(0_i64, |acc, v| acc.checked_add(*v)),
+                1 => values.iter().try_fold(1_i64, |acc, v| acc.checked_mul(*v)),
+                2 => values.iter().min().copied(),
+                3 => values.iter().max().copied(),
+                5 => Some((values[0] > values[1]) as i64),
+                6 => Some((values[0] < values[1]) as i64),
+                _ => Some((values[0] == values[1]) as i64),
+            }
+        }
+    }
+}
+
+// Day 18: reduced snailfish numbers, i.e. nested at most four pairs deep with values below 10.
+
+fn snailfish_element(pairs: u32) -> BoxedStrategy<String> {
+    let regular = (0_u32..10).prop_map(|value| value.to_string());
+    if pairs >= 4 {
+        regular.boxed()
+    } else {
+        prop_oneof![regular, snailfish_pair(pairs + 1)].boxed()
+    }
+}
+
+fn snailfish_pair(pairs: u32) -> BoxedStrategy<String> {
+    (snailfish_element(pairs), snailfish_element(pairs))
+        .prop_map(|(left, right)| format!("[{},{}]", left, right))
+        .boxed()
+}
+
+proptest! {
+    #[test]
+    fn lanternfish_totals_match_naive_simulation((school, days, cycle, delay) in school()) {
+        let total = d06::Population::new(cycle, delay).count(&school, days);
+        prop_assert_eq!(total, simulate_naively(&school, days, cycle, delay));
+    }
+
+    #[test]
+    fn fold_is_idempotent_on_the_fold_line((axis, pos, dots) in dots_around_fold()) {
+        let folded = d13::fold(dots, &axis, &pos);
+        let before_line = |(x, y): &(usize, usize)| if axis == "x" { *x < pos } else { *y < pos };
+        prop_assert!(folded.iter().all(before_line));
+        prop_assert_eq!(d13::fold(folded.clone(), &axis, &pos), folded);
+    }
+
+    #[test]
+    fn dijkstra_distance_is_at_most_any_path_cost((side, cavern, moves) in cavern_with_path()) {
+        let (distances, _previous) = d15::dijkstra(&cavern);
+        let (mut row, mut col, mut cost) = (0, 0, 0);
+        for right in moves {
+            if right {
+                col += 1;
+            } else {
+                row += 1;
+            }
+            cost += cavern[row * side + col];
+        }
+        prop_assert!(*distances.last().unwrap() <= cost);
+    }
+
+    #[test]
+    fn bits_encode_then_decode_round_trips(packet in packet()) {
+        let (versions, value) = d16::decode(&encode(&packet));
+        prop_assert_eq!(versions, version_sum(&packet));
+        prop_assert_eq!(Some(value), evaluate(&packet));
+    }
+
+    #[test]
+    fn snailfish_reduce_keeps_depth_and_values_bounded(
+        left in snailfish_pair(1),
+        right in snailfish_pair(1),
+    ) {
+        let mut sum = d18::add(&d18::parse(&left), &d18::parse(&right));
+        d18::reduce(&mut sum);
+        prop_assert!(sum.iter().all(|s| s.depth <= 3 && s.value < 10));
+    }
+}
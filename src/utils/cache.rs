@@ -0,0 +1,166 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process;
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+
+const CACHE_DIR: &str = "target/input-cache";
+
+// Parsed inputs that can be stored in (and restored from) the on-disk cache.
+pub trait Cached: Sized {
+    // Part of the cache key, with the type's name: bump it whenever the encoding or
+    // the parser producing the value changes, so stale entries are never decoded.
+    const FORMAT: u32 = 1;
+
+    fn encode(&self) -> String;
+    // `None` for anything `encode` can't have produced: never panic on bad data.
+    fn decode(data: &str) -> Option<Self>;
+}
+
+impl<T: Display + FromStr> Cached for Vec<Vec<T>> {
+    fn encode(&self) -> String {
+        self.iter()
+            .map(|row| {
+                row.iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn decode(data: &str) -> Option<Self> {
+        data.lines()
+            .map(|line| {
+                line.split_whitespace()
+                    .map(|v| v.parse().ok())
+                    .collect::<Option<Vec<T>>>()
+            })
+            .collect()
+    }
+}
+
+type Parsed = HashMap<String, Box<dyn Any + Send>>;
+
+fn parsed() -> &'static Mutex<Parsed> {
+    static PARSED: OnceLock<Mutex<Parsed>> = OnceLock::new();
+    PARSED.get_or_init(|| Mutex::new(Parsed::new()))
+}
+
+// 64-bit FNV-1a, stable across runs and toolchains (unlike `DefaultHasher`).
+pub fn fingerprint_bytes(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+pub fn fingerprint(filename: &str) -> u64 {
+    fingerprint_bytes(&fs::read(filename).expect("Something went wrong reading the file"))
+}
+
+// Cache entries start with the length and fingerprint of their payload, so a
+// truncated or otherwise damaged entry is parsed again rather than decoded.
+fn frame(payload: &str) -> String {
+    format!(
+        "{} {:016x}\n{}",
+        payload.len(),
+        fingerprint_bytes(payload.as_bytes()),
+        payload
+    )
+}
+
+fn unframe(data: &str) -> Option<&str> {
+    let (header, payload) = data.split_once('\n')?;
+    let (len, hash) = header.split_once(' ')?;
+    let intact = len.parse() == Ok(payload.len())
+        && u64::from_str_radix(hash, 16) == Ok(fingerprint_bytes(payload.as_bytes()));
+    Some(payload).filter(|_| intact)
+}
+
+// Another run reading the entry never sees it half written: the data goes to a
+// temporary file first, which is then renamed over the entry.
+fn write_atomically(path: &Path, data: &str) -> io::Result<()> {
+    fs::create_dir_all(CACHE_DIR)?;
+    let temporary = path.with_extension(format!("tmp{}", process::id()));
+    fs::write(&temporary, data)?;
+    fs::rename(&temporary, path).inspect_err(|_| {
+        let _ = fs::remove_file(&temporary);
+    })
+}
+
+pub fn load<T, F>(filename: &str, parse: F) -> T
+where
+    T: Cached + Clone + Send + 'static,
+    F: FnOnce(&[String]) -> T,
+{
+    let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
+    let stem = Path::new(filename)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("input");
+    let format = format!("{}@{}", std::any::type_name::<T>(), T::FORMAT);
+    let key = format!(
+        "{}-{:016x}-{:016x}",
+        stem,
+        fingerprint_bytes(format.as_bytes()),
+        fingerprint_bytes(contents.as_bytes())
+    );
+
+    if let Some(found) = parsed()
+        .lock()
+        .unwrap()
+        .get(&key)
+        .and_then(|found| found.downcast_ref::<T>())
+    {
+        return found.clone();
+    }
+
+    let path = Path::new(CACHE_DIR).join(format!("{}.txt", key));
+    let value = match fs::read_to_string(&path)
+        .ok()
+        .as_deref()
+        .and_then(unframe)
+        .and_then(T::decode)
+    {
+        Some(value) => value,
+        None => {
+            let lines = contents
+                .lines()
+                .map(|line| line.to_string())
+                .collect::<Vec<String>>();
+            let value = parse(&lines);
+            // The cache is only an optimisation: failing to write it is not an error.
+            let _ = write_atomically(&path, &frame(&value.encode()));
+            value
+        }
+    };
+
+    parsed()
+        .lock()
+        .unwrap()
+        .insert(key, Box::new(value.clone()));
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn damaged_entries_are_rejected() {
+        let grid = vec![vec![2_i16, 1, 9], vec![3, 9, 8]];
+        let data = frame(&grid.encode());
+        assert_eq!(unframe(&data).and_then(Vec::<Vec<i16>>::decode), Some(grid));
+        // Truncated anywhere, even between rows.
+        for len in 0..data.len() {
+            assert_eq!(unframe(&data[..len]), None);
+        }
+        assert_eq!(unframe(&data.replace('9', "7")), None);
+        assert_eq!(unframe("2 1 9\n3 9 8"), None);
+    }
+}
@@ -0,0 +1,60 @@
+use std::fmt::Display;
+use std::fs;
+use std::iter::Skip;
+use std::process;
+use std::str::FromStr;
+
+// The arguments of a tool in src/bin, usually `[FILE] [--flag value]...`. Mistakes
+// are reported with the tool's usage line, and end the process.
+pub struct Args {
+    usage: &'static str,
+    args: Skip<std::env::Args>,
+}
+
+impl Args {
+    pub fn new(usage: &'static str) -> Self {
+        Args {
+            usage,
+            args: std::env::args().skip(1),
+        }
+    }
+
+    pub fn fail(&self, message: impl Display) -> ! {
+        exit_with(format!("{}\n{}", message, self.usage))
+    }
+
+    // The argument following `flag`, parsed.
+    pub fn value<T: FromStr>(&mut self, flag: &str) -> T {
+        match self.args.next().as_deref().map(str::parse) {
+            Some(Ok(value)) => value,
+            _ => self.fail(format!("{} expects a value", flag)),
+        }
+    }
+
+    // An argument that isn't a known flag: the input file, unless it looks like a flag.
+    pub fn file(&self, arg: String) -> String {
+        if arg.starts_with("--") {
+            self.fail(format!("Unknown option {}", arg));
+        }
+        arg
+    }
+}
+
+impl Iterator for Args {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.args.next()
+    }
+}
+
+pub fn exit_with(message: impl Display) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
+
+pub fn write(filename: &str, contents: impl AsRef<[u8]>) {
+    if let Err(error) = fs::write(filename, contents) {
+        exit_with(format!("{}: {}", filename, error));
+    }
+}
@@ -0,0 +1,158 @@
+use crate::days::d05::{Filter, Raster};
+use std::collections::HashMap;
+use std::fs;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+const CONFIG_FILE: &str = "aoc.conf";
+
+static PARAMS: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+// Whether a value is acceptable for a parameter.
+type Check = fn(&str) -> bool;
+
+// Every parameter a solver reads, with a check of its value. Other keys, in the
+// config file or from `--set`, are rejected: a typo would otherwise silently leave
+// the puzzle's value in place. Values are checked up front too, so a bad one stops
+// the run before any day is solved. Each check parses the type its solver asks for.
+const KNOWN: &[(&str, Check)] = &[
+    ("d04.diagonals", valid::<bool>),
+    ("d05.raster", valid::<Raster>),
+    ("d05.part2_lines", valid::<Filter>),
+    ("d05.min_overlap", positive::<u32>),
+    ("d06.cycle", positive::<i64>),
+    ("d06.newborn_delay", positive::<i64>),
+    ("d06.part1_days", valid::<u64>),
+    ("d06.part2_days", valid::<u64>),
+    ("d11.steps", valid::<u32>),
+    ("d14.part1_steps", valid::<u32>),
+    ("d14.part2_steps", valid::<u32>),
+    ("d15.tiles", positive::<i32>),
+    ("d17.max_initial_y", valid::<i32>),
+];
+
+fn valid<T: FromStr>(value: &str) -> bool {
+    value.parse::<T>().is_ok()
+}
+
+fn positive<T: FromStr + PartialOrd + Default>(value: &str) -> bool {
+    value.parse::<T>().is_ok_and(|value| value > T::default())
+}
+
+fn check(key: &str, value: &str) -> Result<(), String> {
+    match KNOWN.iter().find(|(known, _)| *known == key) {
+        None => Err(format!("Unknown parameter {}", key)),
+        Some((_, valid)) if !valid(value) => Err(format!("Invalid value for {}: {}", key, value)),
+        Some(_) => Ok(()),
+    }
+}
+
+// Parses `[dNN]` sections of `key = value` lines into `dNN.key` parameters.
+fn parse(
+    filename: &str,
+    contents: &str,
+    params: &mut HashMap<String, String>,
+) -> Result<(), String> {
+    let mut section = String::new();
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.split('#').next().unwrap().trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim().to_string();
+            continue;
+        }
+        match line.split_once('=') {
+            Some((key, value)) if !section.is_empty() => {
+                let (key, value) = (format!("{}.{}", section, key.trim()), value.trim());
+                check(&key, value).map_err(|error| format!("{}:{}: {}", filename, i + 1, error))?;
+                params.insert(key, value.to_string());
+            }
+            _ => {
+                return Err(format!(
+                    "{}:{}: expected `key = value` inside a [dNN] section",
+                    filename,
+                    i + 1
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+// Loads the config file (`aoc.conf` unless `--config <file>` is given) and
+// applies `--set dNN.key=value` overrides on top.
+pub fn init<I: Iterator<Item = String>>(mut args: I) -> Result<(), String> {
+    let mut config_file = CONFIG_FILE.to_string();
+    let mut overrides = Vec::<String>::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--config" => config_file = args.next().ok_or("--config expects a file")?,
+            "--set" => overrides.push(args.next().ok_or("--set expects dNN.key=value")?),
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
+
+    let mut params = HashMap::<String, String>::new();
+    match fs::read_to_string(&config_file) {
+        Ok(contents) => parse(&config_file, &contents, &mut params)?,
+        Err(_) if config_file == CONFIG_FILE => {}
+        Err(error) => return Err(format!("Cannot read {}: {}", config_file, error)),
+    }
+    for setting in overrides {
+        let (key, value) = setting.split_once('=').ok_or(format!(
+            "Invalid override {}: expected dNN.key=value",
+            setting
+        ))?;
+        let (key, value) = (key.trim(), value.trim());
+        check(key, value)?;
+        params.insert(key.to_string(), value.to_string());
+    }
+
+    PARAMS
+        .set(params)
+        .map_err(|_| "Configuration already initialised".to_string())
+}
+
+pub fn param<T: FromStr>(key: &str, default: T) -> T {
+    debug_assert!(
+        KNOWN.iter().any(|(known, _)| *known == key),
+        "{} is missing from KNOWN",
+        key
+    );
+    match PARAMS.get().and_then(|params| params.get(key)) {
+        Some(value) => value
+            .parse()
+            .unwrap_or_else(|_| panic!("Invalid value for {}: {}", key, value)),
+        None => default,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(contents: &str) -> Result<HashMap<String, String>, String> {
+        let mut params = HashMap::new();
+        parse("test.conf", contents, &mut params).map(|_| params)
+    }
+
+    #[test]
+    fn values_are_checked_when_read() {
+        let params = parsed("# comment\n[d05]\nraster = bresenham # inline\n").unwrap();
+        assert_eq!(params["d05.raster"], "bresenham");
+        assert_eq!(
+            parsed("[d05]\nraster = exact\nmin_overlap = 0\n"),
+            Err("test.conf:3: Invalid value for d05.min_overlap: 0".to_string())
+        );
+        assert_eq!(
+            parsed("[d06]\npart3_days = 10\n"),
+            Err("test.conf:2: Unknown parameter d06.part3_days".to_string())
+        );
+        assert_eq!(
+            parsed("days = 10\n"),
+            Err("test.conf:1: expected `key = value` inside a [dNN] section".to_string())
+        );
+    }
+}
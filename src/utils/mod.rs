@@ -1,3 +1,10 @@
+#[cfg(feature = "alloc-profile")]
+pub mod alloc;
+pub mod cache;
+pub mod cli;
+pub mod config;
+pub mod report;
+
 use std::fs;
 use std::fmt::Display;
 
@@ -9,15 +16,20 @@ pub fn read_file_lines(filename: &str) ->Vec<String> {
         .collect::<Vec<String>>()
 }
 
-pub fn print_matrix<T:Display>(matrix: &[Vec<T>]) {
+pub fn format_matrix<T:Display>(matrix: &[Vec<T>]) -> String {
     let rows = matrix.len();
     let cols = matrix[0].len();
 
-    println!();
+    let mut result = String::from("\n");
     for row in matrix.iter().take(rows) {
         for val in row.iter().take(cols) {
-            print!("{} ", val);
+            result.push_str(&format!("{} ", val));
         }
-        println!();
+        result.push('\n');
     }
+    result
+}
+
+pub fn print_matrix<T:Display>(matrix: &[Vec<T>]) {
+    print!("{}", format_matrix(matrix));
 }
\ No newline at end of file
@@ -0,0 +1,105 @@
+#[cfg(feature = "alloc-profile")]
+use super::alloc::{measure, Usage};
+use super::cache::fingerprint;
+use std::fmt::Display;
+use std::fs;
+use std::sync::Mutex;
+
+const REPORT_FILE: &str = "target/report.tsv";
+
+struct Answer {
+    day: u8,
+    part: u8,
+    value: String,
+}
+
+static ANSWERS: Mutex<Vec<Answer>> = Mutex::new(Vec::new());
+
+#[cfg(feature = "alloc-profile")]
+static USAGES: Mutex<Vec<(u8, u8, Usage)>> = Mutex::new(Vec::new());
+
+pub fn input_path(day: u8) -> String {
+    format!("input/{:02}.txt", day)
+}
+
+pub fn answer<T: Display>(day: u8, part: u8, value: T) {
+    let value = value.to_string();
+    println!("Day {:02} > Part {}: {}", day, part, value);
+    ANSWERS.lock().unwrap().push(Answer { day, part, value });
+}
+
+#[cfg(not(feature = "alloc-profile"))]
+pub fn run(_day: u8, _part: u8, solver: fn()) {
+    solver();
+}
+
+#[cfg(feature = "alloc-profile")]
+pub fn run(day: u8, part: u8, solver: fn()) {
+    let usage = measure(solver);
+    USAGES.lock().unwrap().push((day, part, usage));
+}
+
+#[cfg(not(feature = "alloc-profile"))]
+fn usage_columns(_day: u8, _part: u8) -> String {
+    String::new()
+}
+
+#[cfg(feature = "alloc-profile")]
+fn usage_columns(day: u8, part: u8) -> String {
+    match USAGES
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(d, p, _)| *d == day && *p == part)
+    {
+        Some((_, _, usage)) => format!(
+            "{:>10}  {:>12}  {:>12}  ",
+            usage.allocations, usage.bytes, usage.peak
+        ),
+        None => format!("{:>10}  {:>12}  {:>12}  ", "-", "-", "-"),
+    }
+}
+
+pub fn print_summary() {
+    let answers = ANSWERS.lock().unwrap();
+    let mut records = Vec::<String>::new();
+
+    println!();
+    println!(
+        "Day  Part  Input         Fingerprint       {}Answer",
+        if cfg!(feature = "alloc-profile") {
+            "    Allocs         Bytes     Peak bytes  "
+        } else {
+            ""
+        }
+    );
+    for answer in answers.iter() {
+        let input = input_path(answer.day);
+        let fingerprint = format!("{:016x}", fingerprint(&input));
+        // Multi-line answers (e.g. day 13) only show their first non-empty line here.
+        let first_line = answer
+            .value
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or("");
+        println!(
+            "{:02}   {}     {}  {}  {}{}",
+            answer.day,
+            answer.part,
+            input,
+            fingerprint,
+            usage_columns(answer.day, answer.part),
+            first_line
+        );
+        records.push(format!(
+            "{}\t{}\t{}\t{}\t{}",
+            answer.day,
+            answer.part,
+            input,
+            fingerprint,
+            answer.value.replace('\n', "\\n")
+        ));
+    }
+
+    let _ = fs::create_dir_all("target").and_then(|_| fs::write(REPORT_FILE, records.join("\n")));
+}